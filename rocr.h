@@ -8,12 +8,115 @@
 #include <stdint.h>
 #include <stdlib.h>
 
+/**
+ * 当前的格式版本
+ * Current format version
+ */
+#define ROCR_ModelBundle_VERSION 1
+
+/**
+ * 默认的旋转置信度阈值，只有 180° 的置信度超过该值才会旋转
+ * Default rotation threshold; lines are only rotated when the 180° confidence exceeds it
+ */
+#define ROCR_Cls_DEFAULT_THRESH 0.9
+
+/**
+ * 模型输入高度
+ * Model input height
+ */
+#define ROCR_Cls_INPUT_HEIGHT 48
+
+/**
+ * 模型输入宽度
+ * Model input width
+ */
+#define ROCR_Cls_INPUT_WIDTH 192
+
+/**
+ * 常规字符的默认最小置信度
+ * Default minimum confidence for regular characters
+ */
+#define ROCR_CtcDecoder_MIN_SCORE_DEFAULT 0.6
+
+/**
+ * 启发式解码中标点符号的默认最小置信度
+ * Default minimum confidence for punctuation in heuristic decoding
+ */
+#define ROCR_CtcDecoder_PUNCT_MIN_SCORE_DEFAULT 0.1
+
+/**
+ * 默认的束宽
+ * Default beam width
+ */
+#define ROCR_CtcDecoder_BEAM_WIDTH_DEFAULT 10
+
+/**
+ * 默认的语言模型权重
+ * Default language model weight
+ */
+#define ROCR_CtcDecoder_LM_WEIGHT_DEFAULT 0.5
+
 /**
  * 默认边界尺寸常量
  * Default rectangle border size constant
  */
 #define ROCR_Det_RECT_BORDER_SIZE 10
 
+/**
+ * 默认的文本框得分阈值，为 0 时不按得分过滤文本框
+ * Default box score threshold; 0 keeps every box regardless of its score
+ */
+#define ROCR_Det_DEFAULT_BOX_THRESH 0.0
+
+/**
+ * 默认的最小边界框尺寸，用于过滤噪声
+ * Default minimum box size for filtering noise
+ */
+#define ROCR_Det_DEFAULT_MIN_BOX_SIZE 5
+
+/**
+ * 默认的边界框合并阈值
+ * Default threshold for merging text boxes
+ */
+#define ROCR_Det_DEFAULT_MERGE_THRESHOLD 1
+
+/**
+ * 竖排文本的高宽比阈值，高宽比不小于该值的文本行视为竖排
+ *
+ * Height/width ratio at which a text line is treated as vertical
+ */
+#define ROCR_VERTICAL_ASPECT_RATIO 1.5
+
+/**
+ * 默认的词典外惩罚（自然对数）
+ * Default out-of-vocabulary penalty (natural log)
+ */
+#define ROCR_Lexicon_OOV_PENALTY_DEFAULT -5.0
+
+/**
+ * 从纯文本语料统计时的默认阶数
+ * Default order when estimating from a plain text corpus
+ */
+#define ROCR_CharNgramLm_ORDER_DEFAULT 3
+
+/**
+ * 推算输出形状时，动态的高和宽使用的尺寸
+ * Size used for dynamic heights and widths when computing output shapes
+ */
+#define ROCR_ModelInfo_PROBE_SIZE 640
+
+/**
+ * 模型输入边长
+ * Model input side length
+ */
+#define ROCR_DocOri_INPUT_SIZE 224
+
+/**
+ * 默认的批量识别大小
+ * Default batch size for batched recognition
+ */
+#define ROCR_Rec_BATCH_SIZE_DEFAULT 6
+
 /**
  * OCR结果状态码
  */
@@ -28,11 +131,33 @@ typedef enum ROCR_RocrStatus {
     ROCR_RocrStatus_NotInitialized = 7,
 } ROCR_RocrStatus;
 
+/**
+ * 模型的预处理和输出约定，不同 PaddleOCR 版本的模型需要使用对应的配置
+ *
+ * Preprocessing and output conventions of a model pair; models from different PaddleOCR
+ * releases need the matching profile
+ */
+typedef struct ROCR_ModelProfile ROCR_ModelProfile;
+
 /**
  * OCR引擎句柄类型
  */
 typedef size_t ROCR_RocrHandle;
 
+/**
+ * 推理运行时设置。threads 为 0 时使用默认线程数；precision、power_mode、memory_mode
+ * 取值 0=Normal、1=High、2=Low；forward_type 取值 0=Auto、1=CPU。C 接口不支持选择
+ * OpenCL、Vulkan、Metal 等 GPU 后端，这些后端需要以对应的 mnn 特性编译，并通过 Rust 接口的
+ * ForwardType 选择；其他取值会使创建引擎失败
+ */
+typedef struct ROCR_RocrRuntimeOptions {
+    unsigned int THREADS;
+    int PRECISION;
+    int POWER_MODE;
+    int FORWARD_TYPE;
+    int MEMORY_MODE;
+} ROCR_RocrRuntimeOptions;
+
 /**
  * 文本框位置信息
  */
@@ -93,6 +218,30 @@ ROCR_RocrHandle rocr_create_engine_with_bytes(const uint8_t *aDetModelData,
                                               int aMergeBoxes,
                                               int aMergeThreshold);
 
+/**
+ * 获取默认的推理运行时设置，可在修改后传给 rocr_create_engine_with_runtime
+ */
+struct ROCR_RocrRuntimeOptions rocr_default_runtime_options(void);
+
+/**
+ * 使用指定的推理运行时设置创建OCR引擎，runtime 为空时使用默认设置，返回引擎句柄
+ */
+ROCR_RocrHandle rocr_create_engine_with_runtime(const char *aDetModelPath,
+                                                const char *aRecModelPath,
+                                                const char *aKeysPath,
+                                                const struct ROCR_RocrRuntimeOptions *aRuntime);
+
+/**
+ * 使用字节数据和指定的推理运行时设置创建OCR引擎，runtime 为空时使用默认设置，返回引擎句柄
+ */
+ROCR_RocrHandle rocr_create_engine_with_bytes_and_runtime(const uint8_t *aDetModelData,
+                                                          size_t aDetModelSize,
+                                                          const uint8_t *aRecModelData,
+                                                          size_t aRecModelSize,
+                                                          const uint8_t *aKeysData,
+                                                          size_t aKeysSize,
+                                                          const struct ROCR_RocrRuntimeOptions *aRuntime);
+
 /**
  * 销毁OCR引擎实例
  */
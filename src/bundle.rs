@@ -0,0 +1,348 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{fmt, path::Path};
+
+use crate::error::{OcrError, OcrResult};
+use crate::profile::{ModelProfile, Normalization, OutputLayout};
+
+/// 单文件模型包，包含检测、识别、可选的方向分类模型、字典和模型配置
+///
+/// 文件格式：8 字节魔数 `ROCRBNDL`，4 字节小端格式版本，4 字节小端清单长度，JSON 清单，
+/// 之后依次为各条目的数据。清单记录每个条目在数据区中的偏移、长度和 SHA-256 校验值
+///
+/// Single-file model bundle holding the detection, recognition and optional direction
+/// classification models, the dictionary and the model profile
+///
+/// File format: the 8-byte magic `ROCRBNDL`, a 4-byte little-endian format version, a 4-byte
+/// little-endian manifest length, the JSON manifest, then the data of each entry. The manifest
+/// records the offset within the data section, the size and the SHA-256 checksum of each entry
+#[derive(Clone)]
+pub struct ModelBundle {
+    /// 检测模型数据
+    /// Detection model data
+    pub det: Vec<u8>,
+    /// 识别模型数据
+    /// Recognition model data
+    pub rec: Vec<u8>,
+    /// 字典文件内容
+    /// Dictionary file content
+    pub keys: Vec<u8>,
+    /// 可选的方向分类模型数据
+    /// Optional direction classification model data
+    pub cls: Option<Vec<u8>>,
+    /// 模型的预处理和输出配置
+    /// Preprocessing and output profile of the models
+    pub profile: ModelProfile,
+}
+
+/// 模型包清单
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    profile: ProfileManifest,
+    entries: Vec<EntryManifest>,
+}
+
+/// 清单中的模型配置
+#[derive(Serialize, Deserialize)]
+struct ProfileManifest {
+    det_mean: [f32; 3],
+    det_std: [f32; 3],
+    det_pad_multiple: u32,
+    rec_mean: [f32; 3],
+    rec_std: [f32; 3],
+    rec_input_height: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rec_max_width: Option<u32>,
+    rec_output_layout: String,
+}
+
+/// 清单中的条目
+#[derive(Serialize, Deserialize)]
+struct EntryManifest {
+    name: String,
+    offset: u64,
+    size: u64,
+    sha256: String,
+}
+
+impl ModelBundle {
+    /// 文件开头的魔数
+    /// Magic bytes at the start of the file
+    pub const MAGIC: &'static [u8; 8] = b"ROCRBNDL";
+
+    /// 当前的格式版本
+    /// Current format version
+    pub const VERSION: u32 = 1;
+
+    /// 由模型和字典数据创建模型包，使用默认的模型配置
+    ///
+    /// Create a bundle from model and dictionary data, with the default model profile
+    pub fn new(det: Vec<u8>, rec: Vec<u8>, keys: Vec<u8>) -> Self {
+        Self {
+            det,
+            rec,
+            keys,
+            cls: None,
+            profile: ModelProfile::default(),
+        }
+    }
+
+    /// 由模型和字典文件创建模型包
+    ///
+    /// Create a bundle from model and dictionary files
+    pub fn from_files(
+        det_model_path: impl AsRef<Path>,
+        rec_model_path: impl AsRef<Path>,
+        keys_path: impl AsRef<Path>,
+    ) -> OcrResult<Self> {
+        Ok(Self::new(
+            std::fs::read(det_model_path)?,
+            std::fs::read(rec_model_path)?,
+            std::fs::read(keys_path)?,
+        ))
+    }
+
+    /// 设置方向分类模型数据
+    ///
+    /// Set the direction classification model data
+    pub fn with_cls(mut self, cls: Vec<u8>) -> Self {
+        self.cls = Some(cls);
+        self
+    }
+
+    /// 设置模型的预处理和输出配置
+    ///
+    /// Set the preprocessing and output profile of the models
+    pub fn with_profile(mut self, profile: ModelProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// 读取模型包文件并校验各条目
+    ///
+    /// Read a bundle file and verify its entries
+    pub fn from_file(path: impl AsRef<Path>) -> OcrResult<Self> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// 解析内存中的模型包并校验各条目
+    ///
+    /// Parse a bundle in memory and verify its entries
+    pub fn from_bytes(data: &[u8]) -> OcrResult<Self> {
+        let header_len = Self::MAGIC.len() + 8;
+        if data.len() < header_len || !data.starts_with(Self::MAGIC) {
+            return Err(OcrError::BundleError("Not a model bundle".to_string()));
+        }
+
+        let read_u32 = |offset: usize| {
+            u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap()) as usize
+        };
+        let version = read_u32(Self::MAGIC.len()) as u32;
+        if version != Self::VERSION {
+            return Err(OcrError::BundleError(format!(
+                "Unsupported bundle version {}, expected {}",
+                version,
+                Self::VERSION
+            )));
+        }
+
+        let manifest_len = read_u32(Self::MAGIC.len() + 4);
+        let manifest = data
+            .get(header_len..header_len + manifest_len)
+            .ok_or_else(|| OcrError::BundleError("Truncated bundle manifest".to_string()))?;
+        let manifest: Manifest =
+            serde_json::from_slice(manifest).map_err(|e| OcrError::JsonError(e.to_string()))?;
+        let payload = &data[header_len + manifest_len..];
+
+        let mut bundle = Self::new(Vec::new(), Vec::new(), Vec::new())
+            .with_profile(manifest.profile.into_profile()?);
+        let (mut has_det, mut has_rec, mut has_keys) = (false, false, false);
+        for entry in &manifest.entries {
+            let content = entry.read(payload)?.to_vec();
+            match entry.name.as_str() {
+                "det" => (bundle.det, has_det) = (content, true),
+                "rec" => (bundle.rec, has_rec) = (content, true),
+                "keys" => (bundle.keys, has_keys) = (content, true),
+                "cls" => bundle.cls = Some(content),
+                // 忽略未知条目，便于以后扩展
+                _ => {}
+            }
+        }
+
+        for (name, present) in [("det", has_det), ("rec", has_rec), ("keys", has_keys)] {
+            if !present {
+                return Err(OcrError::BundleError(format!(
+                    "Bundle is missing the '{}' entry",
+                    name
+                )));
+            }
+        }
+        Ok(bundle)
+    }
+
+    /// 序列化为模型包格式
+    ///
+    /// Serialize into the bundle format
+    pub fn to_bytes(&self) -> OcrResult<Vec<u8>> {
+        let mut entries = vec![("det", &self.det), ("rec", &self.rec), ("keys", &self.keys)];
+        if let Some(cls) = &self.cls {
+            entries.push(("cls", cls));
+        }
+
+        let mut offset = 0;
+        let manifest = Manifest {
+            version: Self::VERSION,
+            profile: ProfileManifest::from_profile(&self.profile),
+            entries: entries
+                .iter()
+                .map(|(name, content)| {
+                    let entry = EntryManifest {
+                        name: name.to_string(),
+                        offset,
+                        size: content.len() as u64,
+                        sha256: sha256_hex(content),
+                    };
+                    offset += content.len() as u64;
+                    entry
+                })
+                .collect(),
+        };
+        let manifest =
+            serde_json::to_vec(&manifest).map_err(|e| OcrError::JsonError(e.to_string()))?;
+
+        let mut data = Vec::with_capacity(16 + manifest.len() + offset as usize);
+        data.extend_from_slice(Self::MAGIC);
+        data.extend_from_slice(&Self::VERSION.to_le_bytes());
+        data.extend_from_slice(&(manifest.len() as u32).to_le_bytes());
+        data.extend_from_slice(&manifest);
+        for (_, content) in entries {
+            data.extend_from_slice(content);
+        }
+        Ok(data)
+    }
+
+    /// 将模型包写入文件
+    ///
+    /// Write the bundle to a file
+    pub fn save(&self, path: impl AsRef<Path>) -> OcrResult<()> {
+        std::fs::write(path, self.to_bytes()?)?;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ModelBundle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ModelBundle")
+            .field("det", &format_args!("{} bytes", self.det.len()))
+            .field("rec", &format_args!("{} bytes", self.rec.len()))
+            .field("keys", &format_args!("{} bytes", self.keys.len()))
+            .field("cls", &self.cls.as_ref().map(|x| x.len()))
+            .field("profile", &self.profile)
+            .finish()
+    }
+}
+
+impl EntryManifest {
+    /// 从数据区读取条目内容并校验
+    fn read<'a>(&self, payload: &'a [u8]) -> OcrResult<&'a [u8]> {
+        let content = usize::try_from(self.offset)
+            .ok()
+            .zip(usize::try_from(self.size).ok())
+            .and_then(|(offset, size)| payload.get(offset..offset.checked_add(size)?))
+            .ok_or_else(|| {
+                OcrError::BundleError(format!("Bundle entry '{}' is truncated", self.name))
+            })?;
+
+        let checksum = sha256_hex(content);
+        if !checksum.eq_ignore_ascii_case(&self.sha256) {
+            return Err(OcrError::BundleError(format!(
+                "Checksum mismatch for bundle entry '{}': expected {}, got {}",
+                self.name, self.sha256, checksum
+            )));
+        }
+        Ok(content)
+    }
+}
+
+impl ProfileManifest {
+    fn from_profile(profile: &ModelProfile) -> Self {
+        Self {
+            det_mean: profile.det_normalization.mean,
+            det_std: profile.det_normalization.std,
+            det_pad_multiple: profile.det_pad_multiple,
+            rec_mean: profile.rec_normalization.mean,
+            rec_std: profile.rec_normalization.std,
+            rec_input_height: profile.rec_input_height,
+            rec_max_width: profile.rec_max_width,
+            rec_output_layout: match profile.rec_output_layout {
+                OutputLayout::SequenceFirst => "sequence-first",
+                OutputLayout::ClassesFirst => "classes-first",
+            }
+            .to_string(),
+        }
+    }
+
+    fn into_profile(self) -> OcrResult<ModelProfile> {
+        let layout = match self.rec_output_layout.as_str() {
+            "sequence-first" => OutputLayout::SequenceFirst,
+            "classes-first" => OutputLayout::ClassesFirst,
+            other => {
+                return Err(OcrError::BundleError(format!(
+                    "Unknown output layout in bundle: {}",
+                    other
+                )))
+            }
+        };
+        let mut profile = ModelProfile::default()
+            .with_det_normalization(Normalization {
+                mean: self.det_mean,
+                std: self.det_std,
+            })
+            .with_det_pad_multiple(self.det_pad_multiple)
+            .with_rec_normalization(Normalization {
+                mean: self.rec_mean,
+                std: self.rec_std,
+            })
+            .with_rec_input_height(self.rec_input_height)
+            .with_rec_output_layout(layout);
+        profile.rec_max_width = self.rec_max_width;
+        Ok(profile)
+    }
+}
+
+/// 计算数据的 SHA-256 十六进制字符串
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_roundtrip() {
+        let bundle = ModelBundle::new(vec![1, 2, 3], vec![4, 5], b"a\nb\n".to_vec())
+            .with_cls(vec![6])
+            .with_profile(ModelProfile::PP_OCRV2.with_rec_max_width(960));
+        let data = bundle.to_bytes().unwrap();
+
+        let loaded = ModelBundle::from_bytes(&data).unwrap();
+        assert_eq!(loaded.det, bundle.det);
+        assert_eq!(loaded.rec, bundle.rec);
+        assert_eq!(loaded.keys, bundle.keys);
+        assert_eq!(loaded.cls, bundle.cls);
+        assert_eq!(loaded.profile, bundle.profile);
+
+        // 篡改最后一个条目的数据
+        let mut corrupted = data.clone();
+        *corrupted.last_mut().unwrap() ^= 0xff;
+        let error = ModelBundle::from_bytes(&corrupted).unwrap_err();
+        assert!(error.to_string().contains("'cls'"));
+
+        assert!(ModelBundle::from_bytes(b"not a bundle").is_err());
+    }
+}
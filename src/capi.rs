@@ -1,4 +1,7 @@
-use crate::{OcrEngine, OcrError};
+use crate::{
+    ForwardType, MemoryMode, OcrEngine, OcrEngineConfig, OcrError, PowerMode, Precision,
+    RuntimeOptions,
+};
 use image::open as image_open;
 use libc::{c_char, c_float, c_int, c_uint, size_t};
 use std::collections::HashMap;
@@ -60,12 +63,70 @@ impl From<OcrError> for RocrStatus {
             OcrError::MNNError(_) => RocrStatus::ProcessError,
             OcrError::EngineError(_) => RocrStatus::NotInitialized,
             OcrError::InputError(_) => RocrStatus::InvalidParam,
+            OcrError::BundleError(_) => RocrStatus::InitError,
             OcrError::OutputError(_) => RocrStatus::ProcessError,
             _ => RocrStatus::ProcessError,
         }
     }
 }
 
+/// 推理运行时设置。threads 为 0 时使用默认线程数；precision、power_mode、memory_mode
+/// 取值 0=Normal、1=High、2=Low；forward_type 取值 0=Auto、1=CPU。C 接口不支持选择
+/// OpenCL、Vulkan、Metal 等 GPU 后端，这些后端需要以对应的 mnn 特性编译，并通过 Rust 接口的
+/// ForwardType 选择；其他取值会使创建引擎失败
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RocrRuntimeOptions {
+    pub threads: c_uint,
+    pub precision: c_int,
+    pub power_mode: c_int,
+    pub forward_type: c_int,
+    pub memory_mode: c_int,
+}
+
+impl RocrRuntimeOptions {
+    /// 转换为运行时设置，取值非法时返回 None
+    fn to_runtime(self) -> Option<RuntimeOptions> {
+        let mut runtime = RuntimeOptions::default()
+            .with_precision(match self.precision {
+                0 => Precision::Normal,
+                1 => Precision::High,
+                2 => Precision::Low,
+                _ => return None,
+            })
+            .with_power_mode(match self.power_mode {
+                0 => PowerMode::Normal,
+                1 => PowerMode::High,
+                2 => PowerMode::Low,
+                _ => return None,
+            })
+            .with_forward_type(match self.forward_type {
+                0 => ForwardType::Auto,
+                1 => ForwardType::CPU,
+                _ => return None,
+            })
+            .with_memory_mode(match self.memory_mode {
+                0 => MemoryMode::Normal,
+                1 => MemoryMode::High,
+                2 => MemoryMode::Low,
+                _ => return None,
+            });
+        if self.threads > 0 {
+            runtime = runtime.with_threads(self.threads);
+        }
+        Some(runtime)
+    }
+}
+
+/// 读取运行时设置，空指针表示使用默认设置
+fn runtime_from_ptr(runtime: *const RocrRuntimeOptions) -> Option<RuntimeOptions> {
+    if runtime.is_null() {
+        Some(RuntimeOptions::default())
+    } else {
+        unsafe { *runtime }.to_runtime()
+    }
+}
+
 /// OCR引擎句柄类型
 pub type RocrHandle = size_t;
 
@@ -225,6 +286,113 @@ pub extern "C" fn rocr_create_engine_with_bytes(
     }
 }
 
+/// 获取默认的推理运行时设置，可在修改后传给 rocr_create_engine_with_runtime
+#[no_mangle]
+pub extern "C" fn rocr_default_runtime_options() -> RocrRuntimeOptions {
+    let runtime = RuntimeOptions::default();
+    RocrRuntimeOptions {
+        threads: runtime.threads.unwrap_or(0),
+        precision: runtime.precision as c_int,
+        power_mode: runtime.power_mode as c_int,
+        forward_type: 0,
+        memory_mode: runtime.memory_mode as c_int,
+    }
+}
+
+/// 使用指定的推理运行时设置创建OCR引擎，runtime 为空时使用默认设置，返回引擎句柄
+#[no_mangle]
+pub extern "C" fn rocr_create_engine_with_runtime(
+    det_model_path: *const c_char,
+    rec_model_path: *const c_char,
+    keys_path: *const c_char,
+    runtime: *const RocrRuntimeOptions,
+) -> RocrHandle {
+    if det_model_path.is_null() || rec_model_path.is_null() || keys_path.is_null() {
+        return 0;
+    }
+
+    let runtime = match runtime_from_ptr(runtime) {
+        Some(runtime) => runtime,
+        None => return 0,
+    };
+
+    let det_path = match unsafe { CStr::from_ptr(det_model_path) }.to_str() {
+        Ok(path) => path,
+        Err(_) => return 0,
+    };
+
+    let rec_path = match unsafe { CStr::from_ptr(rec_model_path) }.to_str() {
+        Ok(path) => path,
+        Err(_) => return 0,
+    };
+
+    let keys_path = match unsafe { CStr::from_ptr(keys_path) }.to_str() {
+        Ok(path) => path,
+        Err(_) => return 0,
+    };
+
+    let config = OcrEngineConfig::default().with_runtime(runtime);
+    match OcrEngine::new_with_options(det_path, rec_path, keys_path, config) {
+        Ok(engine) => {
+            let handle = get_next_handle();
+            let engines = get_engines();
+            if let Ok(mut map) = engines.lock() {
+                map.insert(handle, engine);
+                handle
+            } else {
+                0
+            }
+        }
+        Err(_) => 0,
+    }
+}
+
+/// 使用字节数据和指定的推理运行时设置创建OCR引擎，runtime 为空时使用默认设置，返回引擎句柄
+#[no_mangle]
+pub extern "C" fn rocr_create_engine_with_bytes_and_runtime(
+    det_model_data: *const u8,
+    det_model_size: size_t,
+    rec_model_data: *const u8,
+    rec_model_size: size_t,
+    keys_data: *const u8,
+    keys_size: size_t,
+    runtime: *const RocrRuntimeOptions,
+) -> RocrHandle {
+    if det_model_data.is_null()
+        || rec_model_data.is_null()
+        || keys_data.is_null()
+        || det_model_size == 0
+        || rec_model_size == 0
+        || keys_size == 0
+    {
+        return 0;
+    }
+
+    let runtime = match runtime_from_ptr(runtime) {
+        Some(runtime) => runtime,
+        None => return 0,
+    };
+
+    let det_bytes = unsafe { slice::from_raw_parts(det_model_data, det_model_size) };
+    let rec_bytes = unsafe { slice::from_raw_parts(rec_model_data, rec_model_size) };
+    let keys_bytes = unsafe { slice::from_raw_parts(keys_data, keys_size) };
+
+    let config = OcrEngineConfig::default().with_runtime(runtime);
+    match OcrEngine::new_with_options_and_bytes(det_bytes, rec_bytes, keys_bytes, config) {
+        Ok(engine) => {
+            let handle = get_next_handle();
+            let engines = get_engines();
+            if let Ok(mut map) = engines.lock() {
+                map.insert(handle, engine);
+                handle
+            } else {
+                0
+            }
+        }
+        Err(_) => 0,
+    }
+}
+
 /// 销毁OCR引擎实例
 #[no_mangle]
 pub extern "C" fn rocr_destroy_engine(handle: RocrHandle) -> RocrStatus {
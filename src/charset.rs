@@ -0,0 +1,200 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use crate::error::OcrError;
+
+/// 字符集合，可以是预定义的字符类别或自定义字符
+///
+/// A set of characters, either a predefined character class or custom characters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CharSet {
+    /// 数字 0-9
+    /// Digits 0-9
+    Digits,
+    /// ASCII 大写字母
+    /// ASCII uppercase letters
+    Uppercase,
+    /// ASCII 小写字母
+    /// ASCII lowercase letters
+    Lowercase,
+    /// ASCII 字母
+    /// ASCII letters
+    Letters,
+    /// ASCII 字母和数字
+    /// ASCII letters and digits
+    Alphanumeric,
+    /// 可打印的 ASCII 字符（含空格）
+    /// Printable ASCII characters, including space
+    Ascii,
+    /// 常用汉字（CJK 统一表意文字基本区）
+    /// Common Chinese characters (CJK Unified Ideographs block)
+    Han,
+    /// 自定义字符
+    /// Custom characters
+    Chars(HashSet<char>),
+}
+
+impl CharSet {
+    /// 解析时自定义字符的前缀
+    /// Prefix of custom characters when parsing
+    pub const CHARS_PREFIX: &'static str = "chars:";
+
+    /// 由字符串中的字符创建自定义字符集合
+    ///
+    /// Create a custom set from the characters of a string
+    pub fn chars(chars: &str) -> Self {
+        Self::Chars(chars.chars().collect())
+    }
+
+    /// 是否包含给定字符
+    ///
+    /// Whether the set contains the given character
+    pub fn contains(&self, ch: char) -> bool {
+        match self {
+            Self::Digits => ch.is_ascii_digit(),
+            Self::Uppercase => ch.is_ascii_uppercase(),
+            Self::Lowercase => ch.is_ascii_lowercase(),
+            Self::Letters => ch.is_ascii_alphabetic(),
+            Self::Alphanumeric => ch.is_ascii_alphanumeric(),
+            Self::Ascii => ch == ' ' || ch.is_ascii_graphic(),
+            Self::Han => ('\u{4E00}'..='\u{9FFF}').contains(&ch),
+            Self::Chars(chars) => chars.contains(&ch),
+        }
+    }
+}
+
+impl FromStr for CharSet {
+    type Err = OcrError;
+
+    /// 解析预定义类别名称（digits、uppercase、lowercase、letters、alphanumeric、ascii、han），
+    /// 或以 `chars:` 开头的自定义字符，如 `chars:0123456789.-`
+    ///
+    /// Parse a predefined class name (digits, uppercase, lowercase, letters, alphanumeric, ascii,
+    /// han), or custom characters prefixed with `chars:`, such as `chars:0123456789.-`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(chars) = s.strip_prefix(Self::CHARS_PREFIX) {
+            if chars.is_empty() {
+                return Err(OcrError::InputError("Empty character set".to_string()));
+            }
+            return Ok(Self::chars(chars));
+        }
+
+        let set = match s {
+            "digits" => Self::Digits,
+            "uppercase" => Self::Uppercase,
+            "lowercase" => Self::Lowercase,
+            "letters" => Self::Letters,
+            "alphanumeric" => Self::Alphanumeric,
+            "ascii" => Self::Ascii,
+            "han" => Self::Han,
+            name => {
+                return Err(OcrError::InputError(format!(
+                    "Unknown character class '{}', expected one of digits, uppercase, lowercase, \
+                     letters, alphanumeric, ascii, han, or custom characters as '{}...'",
+                    name,
+                    Self::CHARS_PREFIX
+                )))
+            }
+        };
+        Ok(set)
+    }
+}
+
+/// 字符过滤规则：白名单和黑名单，解码时只会输出允许的字符
+///
+/// Character filter made of a whitelist and a blacklist; decoding only ever emits allowed
+/// characters
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CharFilter {
+    allowed: Vec<CharSet>,
+    denied: Vec<CharSet>,
+}
+
+impl CharFilter {
+    /// 创建不限制任何字符的过滤规则
+    ///
+    /// Create a filter that allows every character
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 加入白名单；设置白名单后只允许其中任一集合包含的字符
+    ///
+    /// Add a set to the whitelist; once a whitelist is set only characters in one of its sets
+    /// are allowed
+    pub fn allow(mut self, set: CharSet) -> Self {
+        self.allowed.push(set);
+        self
+    }
+
+    /// 加入黑名单，黑名单优先于白名单
+    ///
+    /// Add a set to the blacklist, which takes precedence over the whitelist
+    pub fn deny(mut self, set: CharSet) -> Self {
+        self.denied.push(set);
+        self
+    }
+
+    /// 只允许给定字符串中的字符
+    ///
+    /// Only allow the characters of the given string
+    pub fn whitelist(chars: &str) -> Self {
+        Self::new().allow(CharSet::chars(chars))
+    }
+
+    /// 禁止给定字符串中的字符
+    ///
+    /// Forbid the characters of the given string
+    pub fn blacklist(chars: &str) -> Self {
+        Self::new().deny(CharSet::chars(chars))
+    }
+
+    /// 是否不限制任何字符
+    ///
+    /// Whether the filter allows every character
+    pub fn is_unrestricted(&self) -> bool {
+        self.allowed.is_empty() && self.denied.is_empty()
+    }
+
+    /// 是否允许给定字符
+    ///
+    /// Whether the given character is allowed
+    pub fn allows(&self, ch: char) -> bool {
+        (self.allowed.is_empty() || self.allowed.iter().any(|set| set.contains(ch)))
+            && !self.denied.iter().any(|set| set.contains(ch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_char_filter() {
+        let filter = CharFilter::new()
+            .allow("digits".parse().unwrap())
+            .allow(CharSet::chars(".,"))
+            .deny(CharSet::chars("7"));
+        assert!(filter.allows('0'));
+        assert!(filter.allows('.'));
+        assert!(!filter.allows('7'));
+        assert!(!filter.allows('O'));
+
+        let filter = CharFilter::blacklist("lI");
+        assert!(filter.allows('1'));
+        assert!(!filter.allows('l'));
+        assert!(CharFilter::new().is_unrestricted());
+    }
+
+    #[test]
+    fn test_parse_char_set() {
+        assert_eq!("han".parse::<CharSet>().unwrap(), CharSet::Han);
+        assert_eq!(
+            "chars:0-9".parse::<CharSet>().unwrap(),
+            CharSet::chars("0-9")
+        );
+        assert!("digit".parse::<CharSet>().is_err());
+        assert!("chars:".parse::<CharSet>().is_err());
+        assert!("".parse::<CharSet>().is_err());
+    }
+}
@@ -0,0 +1,277 @@
+use image::{imageops::FilterType, DynamicImage, GenericImageView};
+use mnn::Interpreter;
+use ndarray::{Array, ArrayBase, Dim, OwnedRepr};
+use std::path::Path;
+
+use crate::error::{OcrError, OcrResult};
+use crate::model_info::first_tensor_names;
+use crate::runtime::RuntimeOptions;
+
+/// 方向分类结果
+///
+/// Orientation classification result
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClsResult {
+    /// 预测的旋转角度（度）
+    /// Predicted rotation angle in degrees
+    pub angle: u32,
+    /// 预测置信度（0~1）
+    /// Prediction confidence (0~1)
+    pub score: f32,
+}
+
+/// 文本行方向分类模型，判断文本行是否上下颠倒（0°/180°）
+///
+/// Text line direction classifier that predicts whether a line is upside down (0°/180°)
+pub struct Cls {
+    interpreter: Interpreter,
+    session: Option<mnn::Session>,
+    runtime: RuntimeOptions,
+    thresh: f32,
+    // 缓存张量名称以避免重复查找
+    input_tensor_name: Option<String>,
+    output_tensor_name: Option<String>,
+}
+
+impl Cls {
+    /// 默认的旋转置信度阈值，只有 180° 的置信度超过该值才会旋转
+    /// Default rotation threshold; lines are only rotated when the 180° confidence exceeds it
+    pub const DEFAULT_THRESH: f32 = 0.9;
+
+    /// 模型输入高度
+    /// Model input height
+    pub const INPUT_HEIGHT: u32 = 48;
+
+    /// 模型输入宽度
+    /// Model input width
+    pub const INPUT_WIDTH: u32 = 192;
+
+    /// 各类别对应的角度
+    /// Angle of each class
+    const LABELS: [u32; 2] = [0, 180];
+
+    /// 创建新的方向分类器实例
+    ///
+    /// Create a new direction classifier instance
+    pub fn new(interpreter: Interpreter) -> Self {
+        Self {
+            interpreter,
+            session: None,
+            runtime: RuntimeOptions::default(),
+            thresh: Self::DEFAULT_THRESH,
+            input_tensor_name: None,
+            output_tensor_name: None,
+        }
+    }
+
+    /// 从模型文件创建方向分类器
+    ///
+    /// Create a direction classifier from a model file
+    pub fn from_file(model_path: impl AsRef<Path>) -> OcrResult<Self> {
+        let interpreter = Interpreter::from_file(model_path)?;
+        Ok(Self::new(interpreter))
+    }
+
+    /// 从内存字节创建方向分类器
+    ///
+    /// Create a direction classifier from model bytes in memory
+    pub fn from_bytes(model_bytes: impl AsRef<[u8]>) -> OcrResult<Self> {
+        let interpreter = Interpreter::from_bytes(model_bytes)?;
+        Ok(Self::new(interpreter))
+    }
+
+    /// 设置旋转置信度阈值
+    ///
+    /// Set the rotation confidence threshold
+    pub fn with_thresh(mut self, thresh: f32) -> Self {
+        self.thresh = thresh;
+        self
+    }
+
+    /// 设置推理运行时（线程数、精度、后端等）
+    ///
+    /// Set the inference runtime (threads, precision, backend, ...)
+    pub fn with_runtime(mut self, runtime: RuntimeOptions) -> Self {
+        self.runtime = runtime;
+        self.session = None;
+        self
+    }
+
+    /// 预测文本行的方向
+    ///
+    /// Predict the direction of a text line
+    pub fn predict(&mut self, img: &DynamicImage) -> OcrResult<ClsResult> {
+        let input = Self::preprocess(img)?;
+        let output = self.run_model(&input)?;
+        let probs = to_probabilities(&output);
+
+        let (index, score) = probs
+            .iter()
+            .copied()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .ok_or_else(|| OcrError::OutputError("Empty classifier output".to_string()))?;
+
+        let angle = *Self::LABELS.get(index).ok_or_else(|| {
+            OcrError::OutputError(format!(
+                "Unexpected classifier output size: {}",
+                probs.len()
+            ))
+        })?;
+
+        Ok(ClsResult { angle, score })
+    }
+
+    /// 预测文本行方向，如果判断为上下颠倒则旋转 180°
+    ///
+    /// Predict the line direction and rotate the image by 180° if it is upside down
+    pub fn correct(&mut self, img: DynamicImage) -> OcrResult<(DynamicImage, ClsResult)> {
+        let result = self.predict(&img)?;
+        if self.is_upside_down(&result) {
+            Ok((img.rotate180(), result))
+        } else {
+            Ok((img, result))
+        }
+    }
+
+    /// 分类结果是否足以判定文本行上下颠倒
+    ///
+    /// Whether the classification result is confident enough that the line is upside down
+    pub fn is_upside_down(&self, result: &ClsResult) -> bool {
+        result.angle == 180 && result.score > self.thresh
+    }
+
+    /// 保持宽高比缩放到模型输入高度，右侧补零到固定宽度
+    fn preprocess(img: &DynamicImage) -> OcrResult<ArrayBase<OwnedRepr<f32>, Dim<[usize; 4]>>> {
+        let (w, h) = img.dimensions();
+        if w == 0 || h == 0 {
+            return Err(OcrError::InputError("Empty image".to_string()));
+        }
+
+        let ratio = w as f32 / h as f32;
+        let resized_w =
+            ((Self::INPUT_HEIGHT as f32 * ratio).ceil() as u32).clamp(1, Self::INPUT_WIDTH);
+        let resized = img
+            .resize_exact(resized_w, Self::INPUT_HEIGHT, FilterType::Triangle)
+            .to_rgb8();
+
+        let mut input = Array::zeros((
+            1,
+            3,
+            Self::INPUT_HEIGHT as usize,
+            Self::INPUT_WIDTH as usize,
+        ));
+
+        const MEAN: f32 = 0.5;
+        const STD: f32 = 0.5;
+
+        for (x, y, pixel) in resized.enumerate_pixels() {
+            let [r, g, b] = pixel.0;
+            let (x, y) = (x as usize, y as usize);
+            input[[0, 0, y, x]] = (r as f32 / 255.0 - MEAN) / STD;
+            input[[0, 1, y, x]] = (g as f32 / 255.0 - MEAN) / STD;
+            input[[0, 2, y, x]] = (b as f32 / 255.0 - MEAN) / STD;
+        }
+
+        Ok(input)
+    }
+
+    fn run_model(
+        &mut self,
+        input: &ArrayBase<OwnedRepr<f32>, Dim<[usize; 4]>>,
+    ) -> OcrResult<Vec<f32>> {
+        if self.session.is_none() {
+            let session = self
+                .interpreter
+                .create_session(self.runtime.schedule_config())?;
+            self.session = Some(session);
+        }
+
+        // 输入尺寸固定，只需在首次推理时调整张量大小
+        if self.input_tensor_name.is_none() || self.output_tensor_name.is_none() {
+            let session = self.session.as_mut().unwrap();
+            let (input_name, output_name) = first_tensor_names(&self.interpreter, session)?;
+
+            let shape = input.shape();
+            let mut input_tensor = unsafe {
+                self.interpreter
+                    .input_unresized::<f32>(session, &input_name)?
+            };
+            self.interpreter.resize_tensor(
+                &mut input_tensor,
+                [
+                    shape[0] as i32,
+                    shape[1] as i32,
+                    shape[2] as i32,
+                    shape[3] as i32,
+                ],
+            );
+            drop(input_tensor);
+            self.interpreter.resize_session(session);
+
+            self.input_tensor_name = Some(input_name);
+            self.output_tensor_name = Some(output_name);
+        }
+
+        let input_tensor_name = self.input_tensor_name.as_ref().unwrap();
+        let output_tensor_name = self.output_tensor_name.as_ref().unwrap();
+        let session = self.session.as_mut().unwrap();
+
+        let mut input_tensor = self.interpreter.input::<f32>(session, input_tensor_name)?;
+        let mut host_tensor = input_tensor.create_host_tensor_from_device(false);
+        let host_data_mut = host_tensor.host_mut();
+        for (i, val) in input.iter().enumerate() {
+            host_data_mut[i] = *val;
+        }
+        input_tensor.copy_from_host_tensor(&host_tensor)?;
+
+        self.interpreter.run_session(session)?;
+
+        let output = self
+            .interpreter
+            .output::<f32>(session, output_tensor_name)?;
+        output.wait(mnn::ffi::MapType::MAP_TENSOR_READ, true);
+        let output_host_tensor = output.create_host_tensor_from_device(true);
+
+        Ok(output_host_tensor.host().to_vec())
+    }
+}
+
+impl Drop for Cls {
+    fn drop(&mut self) {
+        if let Some(session) = self.session.take() {
+            drop(session);
+        }
+    }
+}
+
+/// 将模型输出转换为概率；导出的模型通常已包含 softmax，否则在此补上
+///
+/// Convert model output to probabilities; exported models usually include the softmax,
+/// otherwise it is applied here
+pub(crate) fn to_probabilities(output: &[f32]) -> Vec<f32> {
+    let sum: f32 = output.iter().sum();
+    let is_normalized = output.iter().all(|x| (0.0..=1.0).contains(x)) && (sum - 1.0).abs() < 1e-3;
+    if is_normalized {
+        return output.to_vec();
+    }
+
+    let max = output.iter().copied().fold(f32::MIN, f32::max);
+    let exps: Vec<f32> = output.iter().map(|x| (x - max).exp()).collect();
+    let sum: f32 = exps.iter().sum();
+    exps.into_iter().map(|x| x / sum).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_probabilities() {
+        assert_eq!(to_probabilities(&[0.2, 0.8]), vec![0.2, 0.8]);
+
+        let probs = to_probabilities(&[1.0, 3.0]);
+        assert!((probs.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+        assert!(probs[1] > 0.85 && probs[1] < 0.9);
+    }
+}
@@ -0,0 +1,860 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::charset::CharFilter;
+use crate::cls::to_probabilities;
+use crate::lm::LanguageModel;
+use crate::pattern::{Pattern, PatternState};
+
+/// CTC 解码方式
+///
+/// CTC decoding mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CtcMode {
+    /// 与 PaddleOCR `CTCLabelDecode` 一致的标准贪心解码：逐帧取最大概率，合并相邻重复后去掉空白
+    /// Standard greedy decoding matching PaddleOCR's `CTCLabelDecode`: take the most probable
+    /// class per frame, collapse adjacent repeats, then drop blanks
+    #[default]
+    Greedy,
+    /// 早期版本使用的启发式解码：按置信度阈值过滤字符，标点不参与重复合并
+    /// Heuristic decoding used by earlier versions: characters are filtered by confidence
+    /// thresholds and punctuation is exempt from repeat merging
+    Heuristic,
+    /// 前缀束搜索解码，可结合词典或语言模型，适合区分 0/O、1/l/I 等易混字符
+    /// Prefix beam search decoding, optionally combined with a lexicon or language model;
+    /// helps to tell apart confusable characters such as 0/O or 1/l/I
+    BeamSearch,
+}
+
+/// 解码得到的单个字典条目
+///
+/// A single decoded dictionary entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct CtcToken {
+    /// 模型输出中的类别索引
+    /// Class index in the model output
+    pub index: usize,
+    /// 对应的字典条目，可能由多个码点组成（如组合字符或 emoji）
+    /// The corresponding dictionary entry, which may span several code points (e.g. combining
+    /// sequences or emoji)
+    pub text: String,
+    /// 置信度（0~1）
+    /// Confidence (0~1)
+    pub score: f32,
+    /// 字符首次出现的时间步
+    /// Time step at which the character first appears
+    pub timestep: usize,
+}
+
+/// CTC 解码器，负责类别索引与字符的映射以及序列解码
+///
+/// CTC decoder that maps class indices to characters and decodes output sequences
+#[derive(Debug, Clone)]
+pub struct CtcDecoder {
+    dict: Vec<String>,
+    use_space_char: bool,
+    blank_index: usize,
+    mode: CtcMode,
+    min_score: f32,
+    punct_min_score: f32,
+    beam_width: usize,
+    lm: Option<Arc<dyn LanguageModel>>,
+    lm_weight: f32,
+    char_filter: CharFilter,
+    // 各类别是否允许输出，不限制字符时为 None
+    mask: Option<Arc<[bool]>>,
+}
+
+/// 束搜索中的候选前缀
+#[derive(Debug, Clone)]
+struct Beam {
+    tokens: Vec<CtcToken>,
+    // 以空白 / 非空白结尾的对数概率
+    blank: f32,
+    non_blank: f32,
+    // 加权后的语言模型得分
+    lm: f32,
+    // 约束模式的匹配状态
+    pattern_state: Option<PatternState>,
+}
+
+impl Beam {
+    fn ctc_score(&self) -> f32 {
+        log_add(self.blank, self.non_blank)
+    }
+
+    fn score(&self) -> f32 {
+        self.ctc_score() + self.lm
+    }
+}
+
+/// 对数空间中的概率相加
+fn log_add(a: f32, b: f32) -> f32 {
+    if a == f32::NEG_INFINITY {
+        return b;
+    }
+    if b == f32::NEG_INFINITY {
+        return a;
+    }
+    let max = a.max(b);
+    max + ((a - max).exp() + (b - max).exp()).ln()
+}
+
+impl CtcDecoder {
+    /// 常规字符的默认最小置信度
+    /// Default minimum confidence for regular characters
+    pub const MIN_SCORE_DEFAULT: f32 = 0.6;
+
+    /// 启发式解码中标点符号的默认最小置信度
+    /// Default minimum confidence for punctuation in heuristic decoding
+    pub const PUNCT_MIN_SCORE_DEFAULT: f32 = 0.1;
+
+    /// 默认的束宽
+    /// Default beam width
+    pub const BEAM_WIDTH_DEFAULT: usize = 10;
+
+    /// 默认的语言模型权重
+    /// Default language model weight
+    pub const LM_WEIGHT_DEFAULT: f32 = 0.5;
+
+    const PUNCTUATIONS: [char; 49] = [
+        ',', '.', '!', '?', ';', ':', '"', '\'', '(', ')', '[', ']', '{', '}', '-', '_', '/', '\\',
+        '|', '@', '#', '$', '%', '&', '*', '+', '=', '~', '，', '。', '！', '？', '；', '：', '、',
+        '「', '」', '『', '』', '（', '）', '【', '】', '《', '》', '—', '…', '·', '～',
+    ];
+
+    /// 由字典条目创建解码器，空白位于索引 0，字典末尾追加空格字符
+    ///
+    /// Create a decoder from dictionary entries, with the blank at index 0 and a space
+    /// character appended after the dictionary
+    pub fn new<S: Into<String>>(dict: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            dict: dict.into_iter().map(Into::into).collect(),
+            use_space_char: true,
+            blank_index: 0,
+            mode: CtcMode::default(),
+            min_score: Self::MIN_SCORE_DEFAULT,
+            punct_min_score: Self::PUNCT_MIN_SCORE_DEFAULT,
+            beam_width: Self::BEAM_WIDTH_DEFAULT,
+            lm: None,
+            lm_weight: Self::LM_WEIGHT_DEFAULT,
+            char_filter: CharFilter::default(),
+            mask: None,
+        }
+    }
+
+    /// 由字典文件内容创建解码器，每行为一个条目
+    ///
+    /// Create a decoder from the content of a dictionary file, one entry per line
+    pub fn from_dict_str(content: &str) -> Self {
+        Self::new(content.lines())
+    }
+
+    /// 由完整的类别表创建解码器，索引 0 为空白占位
+    ///
+    /// Create a decoder from a full class table whose index 0 is a blank placeholder
+    pub fn from_classes<S: Into<String>>(classes: impl IntoIterator<Item = S>) -> Self {
+        Self::new(classes.into_iter().skip(1)).with_use_space_char(false)
+    }
+
+    /// 设置空白类别的索引
+    ///
+    /// Set the index of the blank class
+    pub fn with_blank_index(mut self, blank_index: usize) -> Self {
+        self.blank_index = blank_index;
+        self.refresh_mask();
+        self
+    }
+
+    /// 设置是否在字典末尾追加空格字符（对应 PaddleOCR 的 `use_space_char`）
+    ///
+    /// Set whether a space character is appended after the dictionary (PaddleOCR's `use_space_char`)
+    pub fn with_use_space_char(mut self, use_space_char: bool) -> Self {
+        self.use_space_char = use_space_char;
+        self.refresh_mask();
+        self
+    }
+
+    /// 设置解码方式
+    ///
+    /// Set the decoding mode
+    pub fn with_mode(mut self, mode: CtcMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// 设置常规字符的最小置信度：启发式解码丢弃低于该值的字符，其他解码方式保留并标记为不确定
+    ///
+    /// Set the minimum confidence for regular characters: heuristic decoding drops characters
+    /// below it, while the other modes keep them and flag them as uncertain
+    pub fn with_min_score(mut self, min_score: f32) -> Self {
+        self.min_score = min_score;
+        self
+    }
+
+    /// 设置启发式解码中标点符号的最小置信度
+    ///
+    /// Set the minimum confidence for punctuation in heuristic decoding
+    pub fn with_punct_min_score(mut self, punct_min_score: f32) -> Self {
+        self.punct_min_score = punct_min_score;
+        self
+    }
+
+    /// 设置束搜索的束宽，同时也是每帧参与扩展的候选字符数
+    ///
+    /// Set the beam width of beam search, which is also the number of candidate characters
+    /// expanded per frame
+    pub fn with_beam_width(mut self, beam_width: usize) -> Self {
+        self.beam_width = beam_width.max(1);
+        self
+    }
+
+    /// 设置束搜索使用的词典或语言模型，贪心和启发式解码不使用
+    ///
+    /// Set the lexicon or language model used by beam search; greedy and heuristic decoding
+    /// ignore it
+    pub fn with_language_model(mut self, lm: Arc<dyn LanguageModel>) -> Self {
+        self.lm = Some(lm);
+        self
+    }
+
+    /// 设置语言模型得分相对于识别得分的权重
+    ///
+    /// Set the weight of the language model score relative to the recognition score
+    pub fn with_lm_weight(mut self, lm_weight: f32) -> Self {
+        self.lm_weight = lm_weight;
+        self
+    }
+
+    /// 设置字符过滤规则，解码时只会输出允许的字符
+    ///
+    /// Set the character filter; decoding only ever emits allowed characters
+    pub fn with_char_filter(mut self, char_filter: CharFilter) -> Self {
+        self.char_filter = char_filter;
+        self.refresh_mask();
+        self
+    }
+
+    /// 当前的字符过滤规则
+    ///
+    /// The current character filter
+    pub fn char_filter(&self) -> &CharFilter {
+        &self.char_filter
+    }
+
+    /// 按字符过滤规则重新计算各类别的掩码
+    fn refresh_mask(&mut self) {
+        self.mask = if self.char_filter.is_unrestricted() {
+            None
+        } else {
+            let mask: Vec<bool> = (0..self.num_classes())
+                .map(|index| {
+                    index == self.blank_index
+                        || self
+                            .token(index)
+                            .is_some_and(|text| text.chars().all(|ch| self.char_filter.allows(ch)))
+                })
+                .collect();
+            Some(mask.into())
+        };
+    }
+
+    /// 类别是否允许输出；空白始终允许
+    #[inline]
+    fn is_allowed(&self, index: usize) -> bool {
+        match &self.mask {
+            Some(mask) => mask.get(index).copied().unwrap_or(false),
+            None => true,
+        }
+    }
+
+    /// 置信度是否低于常规字符的最小置信度，即该字符是否不确定
+    ///
+    /// Whether a confidence is below the minimum for regular characters, i.e. the character is
+    /// uncertain
+    pub fn is_uncertain(&self, score: f32) -> bool {
+        score < self.min_score
+    }
+
+    /// 当前的解码方式
+    ///
+    /// The current decoding mode
+    pub fn mode(&self) -> CtcMode {
+        self.mode
+    }
+
+    /// 类别总数（含空白），应与模型输出的最后一维一致
+    ///
+    /// Total number of classes including the blank; should match the last dimension of the model output
+    pub fn num_classes(&self) -> usize {
+        self.dict.len() + usize::from(self.use_space_char) + 1
+    }
+
+    /// 获取类别索引对应的字典条目，空白或越界时返回 `None`
+    ///
+    /// Get the dictionary entry of a class index, or `None` for the blank and out-of-range indices
+    pub fn token(&self, index: usize) -> Option<&str> {
+        let rank = match index.cmp(&self.blank_index) {
+            std::cmp::Ordering::Equal => return None,
+            std::cmp::Ordering::Less => index,
+            std::cmp::Ordering::Greater => index - 1,
+        };
+
+        match self.dict.get(rank) {
+            Some(text) => Some(text),
+            None if self.use_space_char && rank == self.dict.len() => Some(" "),
+            None => None,
+        }
+    }
+
+    /// 解码单个样本的模型输出 [序列长度, 类别数]
+    ///
+    /// Decode the model output of a single sample [sequence, classes]
+    pub fn decode(
+        &self,
+        output: &[f32],
+        sequence_length: usize,
+        num_classes: usize,
+    ) -> Vec<CtcToken> {
+        match self.mode {
+            CtcMode::Greedy => self.decode_greedy(output, sequence_length, num_classes),
+            CtcMode::Heuristic => self.decode_heuristic(output, sequence_length, num_classes),
+            CtcMode::BeamSearch => self
+                .decode_beam(output, sequence_length, num_classes, None)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// 获取单帧中除空白和 `exclude` 外概率最高的 `k` 个字典条目
+    ///
+    /// Get the `k` most probable dictionary entries of a frame, excluding the blank and `exclude`
+    pub fn alternatives(&self, frame: &[f32], k: usize, exclude: usize) -> Vec<(String, f32)> {
+        let mut candidates: Vec<(usize, f32)> = frame
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|&(index, _)| {
+                index != exclude && self.is_allowed(index) && self.token(index).is_some()
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        candidates
+            .into_iter()
+            .take(k)
+            .filter_map(|(index, p)| self.token(index).map(|text| (text.to_string(), p)))
+            .collect()
+    }
+
+    /// 逐帧取最大概率的类别及其概率
+    ///
+    /// Take the most probable class and its probability for every frame
+    fn best_path(
+        &self,
+        output: &[f32],
+        sequence_length: usize,
+        num_classes: usize,
+    ) -> Vec<(usize, f32)> {
+        (0..sequence_length)
+            .map(|t| {
+                let frame = output
+                    .get(t * num_classes..(t + 1) * num_classes)
+                    .unwrap_or(&[]);
+                frame
+                    .iter()
+                    .copied()
+                    .enumerate()
+                    .filter(|&(j, _)| self.is_allowed(j))
+                    .fold(
+                        (0, f32::MIN),
+                        |best, (j, p)| if p > best.1 { (j, p) } else { best },
+                    )
+            })
+            .collect()
+    }
+
+    fn decode_greedy(
+        &self,
+        output: &[f32],
+        sequence_length: usize,
+        num_classes: usize,
+    ) -> Vec<CtcToken> {
+        let mut tokens = Vec::new();
+        let mut previous = None;
+
+        for (timestep, (index, score)) in self
+            .best_path(output, sequence_length, num_classes)
+            .into_iter()
+            .enumerate()
+        {
+            if previous != Some(index) {
+                if let Some(text) = self.token(index) {
+                    tokens.push(CtcToken {
+                        index,
+                        text: text.to_string(),
+                        score,
+                        timestep,
+                    });
+                }
+            }
+            previous = Some(index);
+        }
+
+        tokens
+    }
+
+    /// 加权后的语言模型得分，多码点条目按字符依次计分
+    fn lm_score(&self, context: &[char], text: Option<&str>) -> f32 {
+        let Some(lm) = &self.lm else {
+            return 0.0;
+        };
+        match text {
+            Some(text) => {
+                let mut context = context.to_vec();
+                let mut score = 0.0;
+                for ch in text.chars() {
+                    score += lm.log_prob(&context, ch);
+                    context.push(ch);
+                }
+                self.lm_weight * score
+            }
+            None => self.lm_weight * lm.end_log_prob(context),
+        }
+    }
+
+    /// 用束搜索找出完整匹配 `pattern` 的最可能文本，没有匹配的候选时返回 `None`
+    ///
+    /// Use beam search to find the most probable text that fully matches `pattern`, returning
+    /// `None` when no candidate matches
+    pub fn decode_pattern(
+        &self,
+        output: &[f32],
+        sequence_length: usize,
+        num_classes: usize,
+        pattern: &Pattern,
+    ) -> Option<Vec<CtcToken>> {
+        self.decode_beam(output, sequence_length, num_classes, Some(pattern))
+    }
+
+    fn decode_beam(
+        &self,
+        output: &[f32],
+        sequence_length: usize,
+        num_classes: usize,
+        pattern: Option<&Pattern>,
+    ) -> Option<Vec<CtcToken>> {
+        // 每个模式状态下可接受的类别，束中的状态通常很少，按状态缓存
+        let mut pattern_masks: HashMap<PatternState, Arc<[bool]>> = HashMap::new();
+        let mut beams = vec![(
+            Vec::new(),
+            Beam {
+                tokens: Vec::new(),
+                blank: 0.0,
+                non_blank: f32::NEG_INFINITY,
+                lm: 0.0,
+                pattern_state: pattern.map(Pattern::start_state),
+            },
+        )];
+
+        for timestep in 0..sequence_length {
+            let Some(frame) = output.get(timestep * num_classes..(timestep + 1) * num_classes)
+            else {
+                break;
+            };
+            let probs = to_probabilities(frame);
+            let log_probs: Vec<f32> = probs.iter().map(|p| p.ln()).collect();
+            let blank = log_probs
+                .get(self.blank_index)
+                .copied()
+                .unwrap_or(f32::NEG_INFINITY);
+
+            let mut candidates: Vec<usize> = (0..num_classes)
+                .filter(|&index| index != self.blank_index && self.is_allowed(index))
+                .collect();
+            if pattern.is_none() {
+                // 每帧只扩展概率最高的若干字符
+                self.keep_top(&mut candidates, &probs);
+            }
+
+            let mut next: HashMap<Vec<usize>, Beam> = HashMap::new();
+            for (prefix, beam) in &beams {
+                let last = prefix.last().copied();
+                let total = beam.ctc_score();
+
+                // 输出空白或重复上一个字符时前缀不变
+                let entry = next.entry(prefix.clone()).or_insert_with(|| Beam {
+                    blank: f32::NEG_INFINITY,
+                    non_blank: f32::NEG_INFINITY,
+                    ..beam.clone()
+                });
+                entry.blank = log_add(entry.blank, total + blank);
+                if let Some(last) = last {
+                    entry.non_blank = log_add(entry.non_blank, beam.non_blank + log_probs[last]);
+                }
+
+                // 有模式约束时各前缀可接受的字符不同，按前缀的模式状态筛选后再取概率最高的若干字符
+                let beam_candidates = match (pattern, &beam.pattern_state) {
+                    (Some(pattern), Some(state)) => {
+                        let mask = pattern_masks
+                            .entry(state.clone())
+                            .or_insert_with(|| self.pattern_mask(pattern, state, num_classes));
+                        let mut allowed: Vec<usize> = candidates
+                            .iter()
+                            .copied()
+                            .filter(|&index| mask[index])
+                            .collect();
+                        self.keep_top(&mut allowed, &probs);
+                        Cow::Owned(allowed)
+                    }
+                    _ => Cow::Borrowed(candidates.as_slice()),
+                };
+
+                let context: Vec<char> = beam
+                    .tokens
+                    .iter()
+                    .flat_map(|token| token.text.chars())
+                    .collect();
+                for &index in beam_candidates.iter() {
+                    let Some(text) = self.token(index) else {
+                        continue;
+                    };
+                    let pattern_state = match (pattern, &beam.pattern_state) {
+                        (Some(pattern), Some(state)) => match text
+                            .chars()
+                            .try_fold(state.clone(), |state, ch| pattern.step(&state, ch))
+                        {
+                            Some(next) => Some(next),
+                            None => continue,
+                        },
+                        _ => None,
+                    };
+
+                    // 相同字符之间必须隔有空白才算两个字符
+                    let prob = if last == Some(index) {
+                        beam.blank
+                    } else {
+                        total
+                    } + log_probs[index];
+                    if prob == f32::NEG_INFINITY {
+                        continue;
+                    }
+
+                    let mut extended = prefix.clone();
+                    extended.push(index);
+                    let entry = next.entry(extended).or_insert_with(|| {
+                        let mut tokens = beam.tokens.clone();
+                        tokens.push(CtcToken {
+                            index,
+                            text: text.to_string(),
+                            score: probs[index],
+                            timestep,
+                        });
+                        Beam {
+                            tokens,
+                            blank: f32::NEG_INFINITY,
+                            non_blank: f32::NEG_INFINITY,
+                            lm: beam.lm + self.lm_score(&context, Some(text)),
+                            pattern_state,
+                        }
+                    });
+                    entry.non_blank = log_add(entry.non_blank, prob);
+                }
+            }
+
+            beams = next.into_iter().collect();
+            beams.sort_by(|a, b| b.1.score().total_cmp(&a.1.score()).then(a.0.cmp(&b.0)));
+            beams.truncate(self.beam_width);
+        }
+
+        beams
+            .into_iter()
+            .filter(|(_, beam)| match (pattern, &beam.pattern_state) {
+                (Some(pattern), Some(state)) => pattern.is_accepting(state),
+                _ => true,
+            })
+            .map(|(_, beam)| {
+                let context: Vec<char> = beam
+                    .tokens
+                    .iter()
+                    .flat_map(|token| token.text.chars())
+                    .collect();
+                let score = beam.score() + self.lm_score(&context, None);
+                (score, beam.tokens)
+            })
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, tokens)| tokens)
+    }
+
+    /// 只保留概率最高的 `beam_width` 个类别
+    fn keep_top(&self, candidates: &mut Vec<usize>, probs: &[f32]) {
+        if candidates.len() > self.beam_width {
+            candidates.select_nth_unstable_by(self.beam_width - 1, |&a, &b| {
+                probs[b].total_cmp(&probs[a])
+            });
+            candidates.truncate(self.beam_width);
+        }
+    }
+
+    /// 在给定模式状态下各类别能否被接受
+    fn pattern_mask(
+        &self,
+        pattern: &Pattern,
+        state: &PatternState,
+        num_classes: usize,
+    ) -> Arc<[bool]> {
+        (0..num_classes)
+            .map(|index| {
+                self.token(index).is_some_and(|text| {
+                    text.chars()
+                        .try_fold(state.clone(), |state, ch| pattern.step(&state, ch))
+                        .is_some()
+                })
+            })
+            .collect()
+    }
+
+    /// 条目是否为单个标点符号
+    #[inline]
+    fn is_punctuation(text: &str) -> bool {
+        let mut chars = text.chars();
+        match (chars.next(), chars.next()) {
+            (Some(ch), None) => Self::PUNCTUATIONS.contains(&ch),
+            _ => false,
+        }
+    }
+
+    fn decode_heuristic(
+        &self,
+        output: &[f32],
+        sequence_length: usize,
+        num_classes: usize,
+    ) -> Vec<CtcToken> {
+        let mut results: Vec<CtcToken> = Vec::with_capacity(sequence_length);
+        let mut last_index: Option<usize> = None;
+
+        for (timestep, (index, score)) in self
+            .best_path(output, sequence_length, num_classes)
+            .into_iter()
+            .enumerate()
+        {
+            let Some(text) = self.token(index).filter(|_| score > 0.0) else {
+                last_index = None;
+                continue;
+            };
+            let punctuation = Self::is_punctuation(text);
+            let token = CtcToken {
+                index,
+                text: text.to_string(),
+                score,
+                timestep,
+            };
+
+            let threshold = if punctuation {
+                self.punct_min_score
+            } else {
+                self.min_score
+            };
+
+            if score > threshold {
+                if last_index != Some(index) || punctuation {
+                    results.push(token);
+                }
+                last_index = Some(index);
+            } else if punctuation && score > self.punct_min_score * 0.8 {
+                results.push(token);
+            } else {
+                last_index = None;
+            }
+        }
+
+        // 合并连续重复的标点
+        results.dedup_by(|current, previous| {
+            current.index == previous.index && Self::is_punctuation(&previous.text)
+        });
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 由每帧的类别索引构造概率输出
+    fn one_hot(path: &[(usize, f32)], num_classes: usize) -> Vec<f32> {
+        let mut output = vec![0.0; path.len() * num_classes];
+        for (t, &(index, score)) in path.iter().enumerate() {
+            output[t * num_classes + index] = score;
+        }
+        output
+    }
+
+    #[test]
+    fn test_greedy_decode() {
+        // 类别：0 空白, 1 'a', 2 'b', 3 '.', 4 空格
+        let decoder = CtcDecoder::new(vec!['a', 'b', '.']);
+        assert_eq!(decoder.num_classes(), 5);
+        assert_eq!(decoder.token(4), Some(" "));
+
+        let path = [
+            (1, 0.9),
+            (1, 0.8),
+            (0, 0.9),
+            (1, 0.5),
+            (3, 0.9),
+            (3, 0.9),
+            (4, 0.7),
+            (2, 0.9),
+        ];
+        let output = one_hot(&path, 5);
+        let tokens = decoder.decode(&output, path.len(), 5);
+
+        let text: String = tokens.iter().map(|x| x.text.as_str()).collect();
+        assert_eq!(text, "aa. b");
+        assert_eq!(tokens[0].timestep, 0);
+        assert_eq!(tokens[1].timestep, 3);
+        assert_eq!(tokens[1].score, 0.5);
+    }
+
+    #[test]
+    fn test_heuristic_decode_drops_low_scores() {
+        let decoder = CtcDecoder::new(vec!['a', 'b', '.']).with_mode(CtcMode::Heuristic);
+
+        let path = [
+            (1, 0.9),
+            (1, 0.8),
+            (0, 0.9),
+            (1, 0.5),
+            (3, 0.9),
+            (3, 0.9),
+            (2, 0.9),
+        ];
+        let output = one_hot(&path, 5);
+        let text: String = decoder
+            .decode(&output, path.len(), 5)
+            .iter()
+            .map(|x| x.text.as_str())
+            .collect();
+        assert_eq!(text, "a.b");
+    }
+
+    #[test]
+    fn test_beam_search_with_lexicon() {
+        use crate::lm::Lexicon;
+
+        // 类别：0 空白, 1 'O', 2 '0', 3 'K'
+        let decoder = CtcDecoder::new(vec!['O', '0', 'K'])
+            .with_use_space_char(false)
+            .with_mode(CtcMode::BeamSearch)
+            .with_beam_width(4);
+        let output = vec![
+            0.1, 0.4, 0.5, 0.0, // O / 0 难以区分
+            0.9, 0.05, 0.05, 0.0, //
+            0.1, 0.0, 0.0, 0.9, //
+        ];
+        let decode = |decoder: &CtcDecoder| -> String {
+            decoder
+                .decode(&output, 3, 4)
+                .iter()
+                .map(|x| x.text.as_str())
+                .collect()
+        };
+
+        assert_eq!(decode(&decoder), "0K");
+
+        let decoder = decoder
+            .with_language_model(Arc::new(Lexicon::new(["OK"])))
+            .with_lm_weight(1.0);
+        assert_eq!(decode(&decoder), "OK");
+    }
+
+    #[test]
+    fn test_char_filter_mask() {
+        use crate::charset::CharSet;
+
+        // 类别：0 空白, 1 'O', 2 '0', 3 'l', 4 '1'
+        let decoder = CtcDecoder::new(vec!['O', '0', 'l', '1'])
+            .with_use_space_char(false)
+            .with_char_filter(CharFilter::new().allow(CharSet::Digits));
+        let output = vec![
+            0.0, 0.7, 0.2, 0.05, 0.05, //
+            0.9, 0.0, 0.0, 0.1, 0.0, //
+            0.0, 0.0, 0.0, 0.8, 0.2, //
+        ];
+        let text: String = decoder
+            .decode(&output, 3, 5)
+            .iter()
+            .map(|x| x.text.as_str())
+            .collect();
+        assert_eq!(text, "01");
+
+        let decoder = decoder.with_mode(CtcMode::BeamSearch);
+        let text: String = decoder
+            .decode(&output, 3, 5)
+            .iter()
+            .map(|x| x.text.as_str())
+            .collect();
+        assert_eq!(text, "01");
+    }
+
+    #[test]
+    fn test_decode_pattern() {
+        // 类别：0 空白, 1 'O', 2 '0', 3 'K'
+        let decoder = CtcDecoder::new(vec!['O', '0', 'K']).with_use_space_char(false);
+        let output = vec![
+            0.1, 0.6, 0.3, 0.0, //
+            0.9, 0.05, 0.05, 0.0, //
+            0.1, 0.0, 0.0, 0.9, //
+        ];
+
+        let pattern = Pattern::regex(r"\dK").unwrap();
+        let tokens = decoder.decode_pattern(&output, 3, 4, &pattern).unwrap();
+        let text: String = tokens.iter().map(|x| x.text.as_str()).collect();
+        assert_eq!(text, "0K");
+
+        let pattern = Pattern::mask("00");
+        assert!(decoder.decode_pattern(&output, 3, 4, &pattern).is_none());
+    }
+
+    #[test]
+    fn test_alternatives() {
+        let decoder = CtcDecoder::new(vec!['O', '0', 'D']).with_use_space_char(false);
+        let frame = [0.05, 0.6, 0.3, 0.05];
+        assert_eq!(
+            decoder.alternatives(&frame, 2, 1),
+            vec![("0".to_string(), 0.3), ("D".to_string(), 0.05)]
+        );
+        assert_eq!(
+            decoder.alternatives(&frame, 1, 2),
+            vec![("O".to_string(), 0.6)]
+        );
+    }
+
+    #[test]
+    fn test_blank_index_last() {
+        let decoder = CtcDecoder::new(vec!['a', 'b'])
+            .with_use_space_char(false)
+            .with_blank_index(2);
+        assert_eq!(decoder.token(0), Some("a"));
+        assert_eq!(decoder.token(1), Some("b"));
+        assert_eq!(decoder.token(2), None);
+    }
+
+    #[test]
+    fn test_multi_codepoint_entries() {
+        // 每行一个条目，国旗 emoji 和组合字符占两个码点
+        let decoder = CtcDecoder::from_dict_str("a\n🇨🇳\ne\u{301}\n b\n");
+        assert_eq!(decoder.num_classes(), 6);
+        assert_eq!(decoder.token(2), Some("🇨🇳"));
+        assert_eq!(decoder.token(3), Some("e\u{301}"));
+        assert_eq!(decoder.token(4), Some(" b"));
+
+        let path = [(2, 0.9), (0, 0.9), (3, 0.8), (1, 0.7)];
+        let output = one_hot(&path, 6);
+        let text: String = decoder
+            .decode(&output, path.len(), 6)
+            .iter()
+            .map(|x| x.text.as_str())
+            .collect();
+        assert_eq!(text, "🇨🇳e\u{301}a");
+    }
+}
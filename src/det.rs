@@ -1,11 +1,297 @@
-use image::{DynamicImage, GenericImageView, GrayImage, Luma};
+use image::{
+    imageops::{self, FilterType},
+    DynamicImage, GenericImageView, GrayImage, ImageBuffer, Luma,
+};
 use imageproc::{point::Point, rect::Rect};
-use mnn::{BackendConfig, ForwardType, Interpreter, PowerMode, PrecisionMode, ScheduleConfig};
+use mnn::Interpreter;
 use ndarray::{Array, ArrayBase, Dim, OwnedRepr};
-use std::path::Path;
+use std::{path::Path, str::FromStr};
 
 use crate::efficient_cropping::{EfficientCropper, ImageRef};
-use crate::error::OcrResult;
+use crate::error::{OcrError, OcrResult};
+use crate::geometry::TextQuad;
+use crate::model_info::first_tensor_names;
+use crate::profile::ModelProfile;
+use crate::runtime::RuntimeOptions;
+
+/// 文本概率图，每个像素为该位置属于文本的概率（0~1）
+///
+/// Text probability map; each pixel holds the probability (0~1) that the location belongs to text
+pub type ProbabilityMap = ImageBuffer<Luma<f32>, Vec<f32>>;
+
+/// 带检测得分的文本框
+///
+/// Detected text box with its detection score
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectedBox {
+    /// 轴对齐矩形框
+    /// Axis-aligned rectangle
+    pub rect: Rect,
+    /// 最小外接旋转矩形
+    /// Minimum-area rotated rectangle
+    pub quad: TextQuad,
+    /// 检测得分，即框内文本概率的平均值（0~1）
+    /// Detection score, the mean text probability inside the box (0~1)
+    pub score: f32,
+}
+
+impl DetectedBox {
+    /// 按比例缩放文本框坐标，并限制在给定图像范围内
+    ///
+    /// Scale the box coordinates and clip them to the given image bounds
+    fn scale(self, scale_x: f32, scale_y: f32, width: u32, height: u32) -> Self {
+        let left = ((self.rect.left() as f32 * scale_x).floor() as i32).clamp(0, width as i32 - 1);
+        let top = ((self.rect.top() as f32 * scale_y).floor() as i32).clamp(0, height as i32 - 1);
+        let right = (((self.rect.right() + 1) as f32 * scale_x).ceil() as i32)
+            .clamp(left + 1, width as i32);
+        let bottom = (((self.rect.bottom() + 1) as f32 * scale_y).ceil() as i32)
+            .clamp(top + 1, height as i32);
+
+        Self {
+            rect: Rect::at(left, top).of_size((right - left) as u32, (bottom - top) as u32),
+            quad: self.quad.scale(scale_x, scale_y).clamp(width, height),
+            score: self.score,
+        }
+    }
+
+    /// 按给定偏移平移文本框，并限制在给定图像范围内
+    ///
+    /// Translate the box by the given offset and clip it to the given image bounds
+    fn translate(self, dx: u32, dy: u32, width: u32, height: u32) -> Self {
+        let left = (self.rect.left() + dx as i32).clamp(0, width as i32 - 1);
+        let top = (self.rect.top() + dy as i32).clamp(0, height as i32 - 1);
+        let right = (self.rect.right() + 1 + dx as i32).clamp(left + 1, width as i32);
+        let bottom = (self.rect.bottom() + 1 + dy as i32).clamp(top + 1, height as i32);
+
+        Self {
+            rect: Rect::at(left, top).of_size((right - left) as u32, (bottom - top) as u32),
+            quad: self
+                .quad
+                .translate(dx as f32, dy as f32)
+                .clamp(width, height),
+            score: self.score,
+        }
+    }
+}
+
+/// 分块检测参数，用于超大图像（如工程图纸、海报）
+///
+/// Tiled detection options for very large images such as engineering drawings and posters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TileOptions {
+    /// 分块边长（像素）
+    /// Tile side length in pixels
+    pub tile_size: u32,
+    /// 相邻分块的重叠宽度（像素），应大于最大文字高度以免跨缝文字被截断
+    /// Overlap between neighbouring tiles in pixels; should exceed the largest text height so
+    /// that text crossing a seam is fully contained in at least one tile
+    pub overlap: u32,
+}
+
+impl TileOptions {
+    /// 计算覆盖整幅图像的分块区域，返回 (x, y, 宽, 高) 列表
+    ///
+    /// Compute the tiles covering the whole image as a list of (x, y, width, height)
+    fn tiles(&self, width: u32, height: u32) -> OcrResult<Vec<(u32, u32, u32, u32)>> {
+        if self.tile_size == 0 || self.overlap >= self.tile_size {
+            return Err(OcrError::InputError(format!(
+                "Invalid tiling: overlap {} must be smaller than tile size {}",
+                self.overlap, self.tile_size
+            )));
+        }
+
+        let xs = self.offsets(width);
+        let ys = self.offsets(height);
+        Ok(ys
+            .iter()
+            .flat_map(|&y| {
+                xs.iter().map(move |&x| {
+                    (
+                        x,
+                        y,
+                        self.tile_size.min(width - x),
+                        self.tile_size.min(height - y),
+                    )
+                })
+            })
+            .collect())
+    }
+
+    /// 计算沿某一方向的分块起点
+    ///
+    /// Compute the tile start offsets along one axis
+    fn offsets(&self, length: u32) -> Vec<u32> {
+        if length <= self.tile_size {
+            return vec![0];
+        }
+
+        let step = self.tile_size - self.overlap;
+        let last = length - self.tile_size;
+        let mut offsets: Vec<u32> = (0..last).step_by(step as usize).collect();
+        offsets.push(last);
+        offsets
+    }
+}
+
+/// 检测输入尺寸的限制方式
+///
+/// How the detection input size is limited
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LimitType {
+    /// 长边不超过限制值，较大的图像会被缩小
+    /// The longer side must not exceed the limit; larger images are scaled down
+    #[default]
+    Max,
+    /// 短边不小于限制值，较小的图像会被放大
+    /// The shorter side must be at least the limit; smaller images are scaled up
+    Min,
+}
+
+impl FromStr for LimitType {
+    type Err = OcrError;
+
+    /// 解析 max 或 min
+    ///
+    /// Parse max or min
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "max" => Ok(Self::Max),
+            "min" => Ok(Self::Min),
+            _ => Err(OcrError::InputError(format!("Unknown limit type: {}", s))),
+        }
+    }
+}
+
+/// 文本检测后处理参数
+///
+/// Text detection postprocessing options
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DetOptions {
+    /// 概率图二值化阈值（0~1），概率高于该值的像素视为文本
+    /// Binarization threshold on the probability map (0~1); pixels above it are treated as text
+    pub thresh: f32,
+    /// 文本框得分阈值，框内平均概率低于该值的文本框会被丢弃
+    /// Box score threshold; boxes whose mean probability is below it are dropped
+    pub box_thresh: f32,
+    /// 按文本框大小比例扩展边界的系数，为 `None` 时使用固定像素的 `rect_border_size`
+    /// Ratio for growing boxes in proportion to their size; `None` uses the fixed-pixel `rect_border_size`
+    pub unclip_ratio: Option<f32>,
+    /// 文本框短边的最小尺寸，不大于该值的框会被过滤
+    /// Minimum size of the box's shorter side; boxes not larger than this are filtered out
+    pub min_box_size: u32,
+    /// 文本框长边的最大尺寸，超过该值的框会被过滤
+    /// Maximum size of the box's longer side; larger boxes are filtered out
+    pub max_box_size: Option<u32>,
+    /// 检测输入的边长限制，为 `None` 时使用原始分辨率；检测结果会映射回原图坐标
+    /// Side length limit for the detection input; `None` keeps the original resolution.
+    /// Boxes are mapped back to original image coordinates
+    pub limit_side_len: Option<u32>,
+    /// 边长限制的方式
+    /// How the side length limit is applied
+    pub limit_type: LimitType,
+    /// 分块检测参数，为 `None` 时整图检测；边长限制作用于每个分块
+    /// Tiled detection options; `None` detects on the whole image. The side length limit
+    /// applies to each tile
+    pub tiling: Option<TileOptions>,
+}
+
+impl Default for DetOptions {
+    fn default() -> Self {
+        Self {
+            thresh: Det::DEFAULT_THRESH,
+            box_thresh: Det::DEFAULT_BOX_THRESH,
+            unclip_ratio: None,
+            min_box_size: Det::DEFAULT_MIN_BOX_SIZE,
+            max_box_size: None,
+            limit_side_len: None,
+            limit_type: LimitType::Max,
+            tiling: None,
+        }
+    }
+}
+
+impl DetOptions {
+    /// 设置概率图二值化阈值
+    ///
+    /// Set the probability map binarization threshold
+    pub fn with_thresh(mut self, thresh: f32) -> Self {
+        self.thresh = thresh;
+        self
+    }
+
+    /// 设置文本框得分阈值（PaddleOCR 默认 0.6）
+    ///
+    /// Set the box score threshold (PaddleOCR uses 0.6)
+    pub fn with_box_thresh(mut self, box_thresh: f32) -> Self {
+        self.box_thresh = box_thresh;
+        self
+    }
+
+    /// 设置按比例扩展文本框的系数（PaddleOCR 默认 1.5）
+    ///
+    /// Set the ratio for growing boxes in proportion to their size (PaddleOCR uses 1.5)
+    pub fn with_unclip_ratio(mut self, unclip_ratio: f32) -> Self {
+        self.unclip_ratio = Some(unclip_ratio);
+        self
+    }
+
+    /// 设置文本框短边的最小尺寸
+    ///
+    /// Set the minimum size of the box's shorter side
+    pub fn with_min_box_size(mut self, min_box_size: u32) -> Self {
+        self.min_box_size = min_box_size;
+        self
+    }
+
+    /// 设置文本框长边的最大尺寸
+    ///
+    /// Set the maximum size of the box's longer side
+    pub fn with_max_box_size(mut self, max_box_size: u32) -> Self {
+        self.max_box_size = Some(max_box_size);
+        self
+    }
+
+    /// 设置检测输入的边长限制及限制方式（对应 PaddleOCR 的 `limit_side_len`/`limit_type`）
+    ///
+    /// Set the detection input side length limit and how it is applied
+    /// (PaddleOCR's `limit_side_len`/`limit_type`)
+    pub fn with_limit_side_len(mut self, limit_side_len: u32, limit_type: LimitType) -> Self {
+        self.limit_side_len = Some(limit_side_len);
+        self.limit_type = limit_type;
+        self
+    }
+
+    /// 启用分块检测，按给定的分块边长和重叠宽度切分图像，跨缝的文本框会被合并
+    ///
+    /// Enable tiled detection with the given tile size and overlap; boxes crossing tile
+    /// seams are merged
+    pub fn with_tiling(mut self, tile_size: u32, overlap: u32) -> Self {
+        self.tiling = Some(TileOptions { tile_size, overlap });
+        self
+    }
+
+    /// 按边长限制计算检测输入尺寸，无需缩放时返回 `None`
+    ///
+    /// Compute the detection input size under the side length limit, or `None` if no resize is needed
+    fn limited_size(&self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let limit = self.limit_side_len? as f32;
+        let (w, h) = (width as f32, height as f32);
+
+        let ratio = match self.limit_type {
+            LimitType::Max if w.max(h) > limit => limit / w.max(h),
+            LimitType::Min if w.min(h) < limit => limit / w.min(h),
+            _ => return None,
+        };
+
+        let resized_w = ((w * ratio).round() as u32).max(1);
+        let resized_h = ((h * ratio).round() as u32).max(1);
+        if (resized_w, resized_h) == (width, height) {
+            None
+        } else {
+            Some((resized_w, resized_h))
+        }
+    }
+}
 
 /// 文本检测模型
 ///
@@ -16,6 +302,10 @@ pub struct Det {
     rect_border_size: u32,
     merge_boxes: bool,
     merge_threshold: i32,
+    return_polygons: bool,
+    options: DetOptions,
+    profile: ModelProfile,
+    runtime: RuntimeOptions,
     // 缓存张量名称以避免重复查找
     input_tensor_name: Option<String>,
     output_tensor_name: Option<String>,
@@ -28,13 +318,17 @@ impl Det {
     /// Default rectangle border size constant
     pub const RECT_BORDER_SIZE: u32 = 10;
 
-    /// 二值化阈值常量
-    /// Binary threshold constant
-    const THRESHOLD: u8 = 200;
+    /// 默认的概率图二值化阈值
+    /// Default probability map binarization threshold
+    pub const DEFAULT_THRESH: f32 = 200.0 / 255.0;
 
-    /// 最小边界框尺寸阈值，用于过滤噪声
-    /// Minimum box size threshold for filtering noise
-    const MIN_BOX_SIZE: u32 = 5;
+    /// 默认的文本框得分阈值，为 0 时不按得分过滤文本框
+    /// Default box score threshold; 0 keeps every box regardless of its score
+    pub const DEFAULT_BOX_THRESH: f32 = 0.0;
+
+    /// 默认的最小边界框尺寸，用于过滤噪声
+    /// Default minimum box size for filtering noise
+    pub const DEFAULT_MIN_BOX_SIZE: u32 = 5;
 
     /// 默认的边界框合并阈值
     /// Default threshold for merging text boxes
@@ -51,6 +345,10 @@ impl Det {
             rect_border_size: Self::RECT_BORDER_SIZE,
             merge_boxes: false,
             merge_threshold: Self::DEFAULT_MERGE_THRESHOLD,
+            return_polygons: false,
+            options: DetOptions::default(),
+            profile: ModelProfile::default(),
+            runtime: RuntimeOptions::default(),
             input_tensor_name: None,
             output_tensor_name: None,
             last_input_shape: None,
@@ -68,6 +366,10 @@ impl Det {
             rect_border_size: Self::RECT_BORDER_SIZE,
             merge_boxes: false,
             merge_threshold: Self::DEFAULT_MERGE_THRESHOLD,
+            return_polygons: false,
+            options: DetOptions::default(),
+            profile: ModelProfile::default(),
+            runtime: RuntimeOptions::default(),
             input_tensor_name: None,
             output_tensor_name: None,
             last_input_shape: None,
@@ -85,6 +387,10 @@ impl Det {
             rect_border_size: Self::RECT_BORDER_SIZE,
             merge_boxes: false,
             merge_threshold: Self::DEFAULT_MERGE_THRESHOLD,
+            return_polygons: false,
+            options: DetOptions::default(),
+            profile: ModelProfile::default(),
+            runtime: RuntimeOptions::default(),
             input_tensor_name: None,
             output_tensor_name: None,
             last_input_shape: None,
@@ -115,13 +421,80 @@ impl Det {
         self
     }
 
+    /// 设置检测后处理参数
+    ///
+    /// Set the detection postprocessing options
+    pub fn with_options(mut self, options: DetOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// 获取当前的检测后处理参数
+    ///
+    /// Get the current detection postprocessing options
+    pub fn options(&self) -> &DetOptions {
+        &self.options
+    }
+
+    /// 设置模型的预处理配置，默认为 PP-OCRv5 轻量模型的配置
+    ///
+    /// Set the preprocessing profile of the model; defaults to the PP-OCRv5 mobile profile
+    pub fn with_profile(mut self, profile: ModelProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// 获取当前的模型预处理配置
+    ///
+    /// Get the current model preprocessing profile
+    pub fn profile(&self) -> &ModelProfile {
+        &self.profile
+    }
+
+    /// 设置推理运行时（线程数、精度、后端等），默认使用低精度、高性能模式
+    ///
+    /// Set the inference runtime (threads, precision, backend, ...); defaults to low precision
+    /// in high power mode
+    pub fn with_runtime(mut self, runtime: RuntimeOptions) -> Self {
+        self.runtime = runtime;
+        // 已创建的会话按新设置重建
+        self.session = None;
+        self.last_input_shape = None;
+        self
+    }
+
+    /// 获取当前的推理运行时设置
+    ///
+    /// Get the current inference runtime settings
+    pub fn runtime(&self) -> &RuntimeOptions {
+        &self.runtime
+    }
+
+    /// 设置是否为旋转文本框同时返回轮廓多边形
+    ///
+    /// Set whether to also return the contour polygon with each rotated text box
+    pub fn with_polygons(mut self, return_polygons: bool) -> Self {
+        self.return_polygons = return_polygons;
+        self
+    }
+
     /// 在图像中查找文本区域，返回矩形框列表
     ///
     /// Find text regions in the image and return a list of rectangle boxes
     pub fn find_text_rect(&mut self, img: &DynamicImage) -> OcrResult<Vec<Rect>> {
-        let input = Self::preprocess(img)?;
-        let output = self.run_model(&input, img.width(), img.height())?;
-        let boxes = self.find_box(&output, img.width(), img.height());
+        let boxes = self.find_text_boxes(img)?;
+        Ok(boxes.into_iter().map(|x| x.rect).collect())
+    }
+
+    /// 在图像中查找文本区域，返回带检测得分的文本框列表
+    ///
+    /// 得分可用于过滤误检或按可靠程度对文本区域排序。
+    ///
+    /// Find text regions in the image and return text boxes with their detection scores
+    ///
+    /// The scores can be used to filter out false positives or rank regions by reliability.
+    pub fn find_text_boxes(&mut self, img: &DynamicImage) -> OcrResult<Vec<DetectedBox>> {
+        let boxes = self.detect(img)?;
 
         // 如果启用了边界框合并功能，则合并重叠的边界框
         if self.merge_boxes {
@@ -131,6 +504,50 @@ impl Det {
         }
     }
 
+    /// 计算图像的文本概率图，坐标与原图一致
+    ///
+    /// 返回检测模型的原始分割输出（未经二值化和轮廓提取），可用于自定义后处理或排查漏检。
+    /// 设置了边长限制时概率图会被缩放回原图尺寸；启用分块检测时各分块的结果会被拼接，
+    /// 重叠区域取最大值。
+    ///
+    /// Compute the text probability map of the image in original image coordinates
+    ///
+    /// Returns the raw segmentation output of the detection model (before binarization and
+    /// contour extraction), useful for custom postprocessing or for debugging missed lines.
+    /// With a side length limit the map is resized back to the original size; with tiling the
+    /// tile maps are stitched together, taking the maximum in overlapping regions.
+    pub fn probability_map(&mut self, img: &DynamicImage) -> OcrResult<ProbabilityMap> {
+        let (width, height) = img.dimensions();
+
+        let tiling = match self.options.tiling {
+            Some(tiling) if width > tiling.tile_size || height > tiling.tile_size => tiling,
+            _ => return self.probability_map_single(img),
+        };
+
+        let mut map = ProbabilityMap::new(width, height);
+        for (x, y, tile_w, tile_h) in tiling.tiles(width, height)? {
+            let tile_map = self.probability_map_single(&img.crop_imm(x, y, tile_w, tile_h))?;
+            for (tx, ty, pixel) in tile_map.enumerate_pixels() {
+                let target = map.get_pixel_mut(x + tx, y + ty);
+                target.0[0] = target.0[0].max(pixel.0[0]);
+            }
+        }
+
+        Ok(map)
+    }
+
+    /// 在图像中查找文本区域，返回最小外接旋转矩形（四个角点）列表
+    ///
+    /// 适用于倾斜或旋转的文本行，边框不会吞并相邻的文本行。
+    ///
+    /// Find text regions in the image and return their minimum-area rotated rectangles (four corner points)
+    ///
+    /// Suitable for skewed or rotated text lines, as the boxes do not swallow neighbouring lines.
+    pub fn find_text_quads(&mut self, img: &DynamicImage) -> OcrResult<Vec<TextQuad>> {
+        let boxes = self.detect(img)?;
+        Ok(boxes.into_iter().map(|x| x.quad).collect())
+    }
+
     /// 在图像中查找文本区域，返回裁剪后的子图像列表
     ///
     /// Find text regions in the image and return a list of cropped sub-images
@@ -173,17 +590,131 @@ impl Det {
         Ok(results)
     }
 
-    fn preprocess(img: &DynamicImage) -> OcrResult<ArrayBase<OwnedRepr<f32>, Dim<[usize; 4]>>> {
+    /// 使用旋转四边形框查找文本区域，返回透视校正后的水平文本行图像
+    ///
+    /// Find text regions using rotated quads and return perspective-corrected, horizontal text line images
+    pub fn find_text_img_rotated(&mut self, img: &DynamicImage) -> OcrResult<Vec<DynamicImage>> {
+        let quads = self.find_text_quads(img)?;
+
+        if quads.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let image_ref = ImageRef::from(img.clone());
+        Ok(EfficientCropper::batch_perspective_crop(&image_ref, &quads))
+    }
+
+    /// 运行检测模型并进行后处理，不合并文本框
+    ///
+    /// 如果设置了边长限制，会先缩放图像再推理，并将检测结果映射回原图坐标。
+    ///
+    /// Run the detection model and postprocess, without merging boxes
+    ///
+    /// If a side length limit is set, the image is resized before inference and
+    /// the boxes are mapped back to original image coordinates.
+    fn detect(&mut self, img: &DynamicImage) -> OcrResult<Vec<DetectedBox>> {
+        match self.options.tiling {
+            Some(tiling) if img.width() > tiling.tile_size || img.height() > tiling.tile_size => {
+                self.detect_tiled(img, tiling)
+            }
+            _ => self.detect_single(img),
+        }
+    }
+
+    /// 分块检测：在重叠的分块上分别检测，映射回全图坐标后合并跨缝的文本框
+    ///
+    /// Tiled detection: detect on overlapping tiles, map the boxes back to global coordinates
+    /// and merge the boxes that cross tile seams
+    fn detect_tiled(
+        &mut self,
+        img: &DynamicImage,
+        tiling: TileOptions,
+    ) -> OcrResult<Vec<DetectedBox>> {
+        let (width, height) = img.dimensions();
+
+        let mut tagged = Vec::new();
+        for (tile_index, (x, y, tile_w, tile_h)) in
+            tiling.tiles(width, height)?.into_iter().enumerate()
+        {
+            let tile = img.crop_imm(x, y, tile_w, tile_h);
+            for detected in self.detect_single(&tile)? {
+                tagged.push((tile_index, detected.translate(x, y, width, height)));
+            }
+        }
+
+        Ok(Self::merge_tile_boxes(tagged))
+    }
+
+    /// 在单张图像（或分块）上运行检测
+    ///
+    /// Run detection on a single image (or tile)
+    fn detect_single(&mut self, img: &DynamicImage) -> OcrResult<Vec<DetectedBox>> {
+        let (width, height) = img.dimensions();
+        let map = self.infer(img)?;
+        let (map_w, map_h) = map.dimensions();
+
+        if (map_w, map_h) == (width, height) {
+            return Ok(self.find_boxes(&map, width, height, 1.0));
+        }
+
+        // 固定像素的边界扩展按原图尺寸计算
+        let scale_x = width as f32 / map_w as f32;
+        let scale_y = height as f32 / map_h as f32;
+        let boxes = self.find_boxes(&map, map_w, map_h, 1.0 / scale_x.max(scale_y));
+
+        Ok(boxes
+            .into_iter()
+            .map(|x| x.scale(scale_x, scale_y, width, height))
+            .collect())
+    }
+
+    /// 按边长限制缩放图像并运行检测模型，返回模型输入分辨率下的概率图
+    ///
+    /// Resize the image under the side length limit and run the detection model, returning
+    /// the probability map at the model input resolution
+    fn infer(&mut self, img: &DynamicImage) -> OcrResult<ProbabilityMap> {
+        let (width, height) = img.dimensions();
+
+        match self.options.limited_size(width, height) {
+            Some((resized_w, resized_h)) => {
+                let resized = img.resize_exact(resized_w, resized_h, FilterType::Triangle);
+                let input = self.preprocess(&resized)?;
+                self.run_model(&input, resized_w, resized_h)
+            }
+            None => {
+                let input = self.preprocess(img)?;
+                self.run_model(&input, width, height)
+            }
+        }
+    }
+
+    /// 在单张图像（或分块）上计算原图坐标下的概率图
+    ///
+    /// Compute the probability map of a single image (or tile) in original image coordinates
+    fn probability_map_single(&mut self, img: &DynamicImage) -> OcrResult<ProbabilityMap> {
+        let (width, height) = img.dimensions();
+        let map = self.infer(img)?;
+
+        if map.dimensions() == (width, height) {
+            Ok(map)
+        } else {
+            Ok(imageops::resize(&map, width, height, FilterType::Triangle))
+        }
+    }
+
+    fn preprocess(
+        &self,
+        img: &DynamicImage,
+    ) -> OcrResult<ArrayBase<OwnedRepr<f32>, Dim<[usize; 4]>>> {
         let (w, h) = img.dimensions();
-        let pad_w = Self::get_pad_length(w);
-        let pad_h = Self::get_pad_length(h);
+        let pad_w = self.profile.det_pad_length(w);
+        let pad_h = self.profile.det_pad_length(h);
 
         // 预分配数组空间
         let mut input = Array::zeros((1, 3, pad_h as usize, pad_w as usize));
 
         // 归一化参数
-        const MEAN: [f32; 3] = [0.485, 0.456, 0.406];
-        const STD: [f32; 3] = [0.229, 0.224, 0.225];
+        let normalization = self.profile.det_normalization;
 
         // 转换为RGB格式以便批量处理
         let rgb_img = img.to_rgb8();
@@ -210,9 +741,9 @@ impl Det {
                 let [r, g, b] = pixel.0;
 
                 // 计算归一化值
-                let norm_r = (r as f32 / 255.0 - MEAN[0]) / STD[0];
-                let norm_g = (g as f32 / 255.0 - MEAN[1]) / STD[1];
-                let norm_b = (b as f32 / 255.0 - MEAN[2]) / STD[2];
+                let norm_r = normalization.apply(0, r);
+                let norm_g = normalization.apply(1, g);
+                let norm_b = normalization.apply(2, b);
 
                 local_updates.push(((0, 0, y, x), norm_r));
                 local_updates.push(((0, 1, y, x), norm_g));
@@ -234,37 +765,23 @@ impl Det {
         input: &ArrayBase<OwnedRepr<f32>, Dim<[usize; 4]>>,
         width: u32,
         height: u32,
-    ) -> OcrResult<GrayImage> {
-        let pad_w = Self::get_pad_length(width);
+    ) -> OcrResult<ProbabilityMap> {
+        let pad_w = self.profile.det_pad_length(width);
 
-        // 优化配置：使用更好的性能配置
+        // 按运行时设置创建会话
         if self.session.is_none() {
-            let mut config = ScheduleConfig::new();
-            config.set_type(ForwardType::Auto);
-
-            let mut backend_config = BackendConfig::new();
-            // 使用更低精度以提升性能（如果支持的话，否则使用Normal）
-            backend_config.set_precision_mode(PrecisionMode::Low);
-            backend_config.set_power_mode(PowerMode::High);
-
-            config.set_backend_config(backend_config);
-
-            let session = self.interpreter.create_session(config)?;
+            let session = self
+                .interpreter
+                .create_session(self.runtime.schedule_config())?;
             self.session = Some(session);
         }
 
         // 获取或缓存输入输出张量名称
         if self.input_tensor_name.is_none() || self.output_tensor_name.is_none() {
             let session = self.session.as_ref().unwrap();
-            let inputs = self.interpreter.inputs(session);
-            let outputs = self.interpreter.outputs(session);
-
-            // 获取第一个输入和输出张量的信息
-            let input_info = inputs.iter().next().unwrap();
-            let output_info = outputs.iter().next().unwrap();
-
-            self.input_tensor_name = Some(input_info.name().to_string());
-            self.output_tensor_name = Some(output_info.name().to_string());
+            let (input_name, output_name) = first_tensor_names(&self.interpreter, session)?;
+            self.input_tensor_name = Some(input_name);
+            self.output_tensor_name = Some(output_name);
         }
 
         let input_tensor_info = self.input_tensor_name.as_ref().unwrap();
@@ -335,57 +852,147 @@ impl Det {
             output_host_tensor.host().to_vec() // 复制数据到新的向量
         };
 
-        // 构建灰度图像
-        let img = image::ImageBuffer::from_fn(width, height, |x, y| {
+        // 构建概率图，去掉填充区域
+        let map = ProbabilityMap::from_fn(width, height, |x, y| {
             let index = (y * pad_w + x) as usize;
-            if index < output_data.len() {
-                Luma([(output_data[index] * 255.0).min(255.0) as u8])
-            } else {
-                Luma([0])
-            }
+            Luma([output_data.get(index).copied().unwrap_or(0.0)])
         });
 
-        Ok(img)
+        Ok(map)
     }
 
-    fn find_box(&self, img: &GrayImage, width: u32, height: u32) -> Vec<Rect> {
-        let contours =
-            imageproc::contours::find_contours_with_threshold::<u32>(img, Self::THRESHOLD);
+    fn find_boxes(
+        &self,
+        map: &ProbabilityMap,
+        width: u32,
+        height: u32,
+        border_scale: f32,
+    ) -> Vec<DetectedBox> {
+        // 按概率阈值二值化
+        let thresh = self.options.thresh;
+        let binary = GrayImage::from_fn(width, height, |x, y| {
+            Luma([if map.get_pixel(x, y).0[0] > thresh {
+                255
+            } else {
+                0
+            }])
+        });
+        let contours = imageproc::contours::find_contours::<u32>(&binary);
 
-        // 先获取所有有效的边界框
-        let mut boxes: Vec<Rect> = contours
+        contours
             .into_iter()
             .filter(|x| x.parent.is_none()) // 只保留外部轮廓
-            .filter_map(|x| Self::bounding_rect(&x.points))
-            .collect();
+            .filter_map(|x| {
+                let mut quad = TextQuad::from_points(&x.points)?;
+
+                // 按短边和长边过滤噪声及异常框
+                if !self.is_valid_size(quad.width(), quad.height()) {
+                    return None;
+                }
+
+                // 过滤框内平均概率过低的文本框
+                let score = Self::box_score(map, &quad);
+                if score < self.options.box_thresh {
+                    return None;
+                }
 
-        // 应用边界扩展
-        boxes = boxes
-            .into_iter()
-            .map(|x| {
                 // 扩展边界，确保完全包含文本
-                let left = (x.left() - self.rect_border_size as i32).max(0);
-                let top = (x.top() - self.rect_border_size as i32).max(0);
+                let rect = Self::bounding_rect(&x.points)?;
+                let border = self
+                    .unclip_distance(rect.width() as f32, rect.height() as f32, border_scale)
+                    .round() as i32;
+                let left = (rect.left() - border).max(0);
+                let top = (rect.top() - border).max(0);
 
                 // 确保扩展后的右边界和下边界不超出图像
-                let right = (x.right() + self.rect_border_size as i32).min(width as i32 - 1);
-                let bottom = (x.bottom() + self.rect_border_size as i32).min(height as i32 - 1);
+                let right = (rect.right() + border).min(width as i32 - 1);
+                let bottom = (rect.bottom() + border).min(height as i32 - 1);
 
                 // 计算新的宽度和高度
                 let rect_width = (right - left + 1) as u32;
                 let rect_height = (bottom - top + 1) as u32;
+                let rect = Rect::at(left, top).of_size(rect_width, rect_height);
+
+                if self.return_polygons {
+                    quad.polygon = Some(Self::contour_polygon(&x.points));
+                }
 
-                Rect::at(left, top).of_size(rect_width, rect_height)
+                // 沿文本框自身方向扩展边界，并限制在图像范围内
+                let distance = self.unclip_distance(quad.width(), quad.height(), border_scale);
+                let quad = quad.expand(distance).clamp(width, height);
+
+                Some(DetectedBox { rect, quad, score })
             })
-            .collect();
+            .collect()
+    }
+
+    /// 按短边和长边检查文本框尺寸是否有效
+    ///
+    /// Check whether a box size is valid by its shorter and longer sides
+    fn is_valid_size(&self, width: f32, height: f32) -> bool {
+        let short_side = width.min(height);
+        let long_side = width.max(height);
+
+        short_side > self.options.min_box_size as f32
+            && self
+                .options
+                .max_box_size
+                .is_none_or(|max_size| long_side <= max_size as f32)
+    }
+
+    /// 计算文本框的扩展距离：按比例扩展时为 面积 × 系数 / 周长，否则为按 `border_scale`
+    /// 缩放后的固定像素
+    ///
+    /// Compute the border distance for a box: area × ratio / perimeter when unclipping
+    /// proportionally, otherwise the fixed pixel size scaled by `border_scale`
+    fn unclip_distance(&self, width: f32, height: f32, border_scale: f32) -> f32 {
+        match self.options.unclip_ratio {
+            Some(ratio) if width + height > 0.0 => {
+                width * height * ratio / (2.0 * (width + height))
+            }
+            _ => self.rect_border_size as f32 * border_scale,
+        }
+    }
+
+    /// 计算文本框内的平均概率作为得分
+    ///
+    /// Compute the mean probability inside a box as its score
+    fn box_score(map: &ProbabilityMap, quad: &TextQuad) -> f32 {
+        let rect = quad.bounding_rect(map.width(), map.height());
+
+        let mut sum = 0.0f32;
+        let mut count = 0u32;
+        for y in rect.top()..=rect.bottom() {
+            for x in rect.left()..=rect.right() {
+                if quad.contains(Point::new(x as f32, y as f32)) {
+                    sum += map.get_pixel(x as u32, y as u32).0[0];
+                    count += 1;
+                }
+            }
+        }
 
-        boxes
+        if count == 0 {
+            0.0
+        } else {
+            sum / count as f32
+        }
+    }
+
+    /// 将轮廓点简化为多边形
+    ///
+    /// Simplify contour points into a polygon
+    fn contour_polygon(points: &[Point<u32>]) -> Vec<Point<f32>> {
+        let epsilon = (0.002 * imageproc::geometry::arc_length(points, true)).max(1.0);
+        imageproc::geometry::approximate_polygon_dp(points, epsilon, true)
+            .into_iter()
+            .map(|p| Point::new(p.x as f32, p.y as f32))
+            .collect()
     }
 
     /// 合并重叠的边界框
     ///
     /// Merge overlapping bounding boxes
-    fn merge_overlapping_boxes(boxes: Vec<Rect>, threshold: i32) -> Vec<Rect> {
+    fn merge_overlapping_boxes(boxes: Vec<DetectedBox>, threshold: i32) -> Vec<DetectedBox> {
         if boxes.is_empty() {
             return boxes;
         }
@@ -400,9 +1007,13 @@ impl Det {
             let mut i = 0;
 
             while i < boxes_to_process.len() {
-                if Self::boxes_overlap_with_threshold(&merged, &boxes_to_process[i], threshold) {
+                if Self::boxes_overlap_with_threshold(
+                    &merged.rect,
+                    &boxes_to_process[i].rect,
+                    threshold,
+                ) {
                     // 合并边界框
-                    merged = Self::merge_boxes(&merged, &boxes_to_process[i]);
+                    merged = Self::merge_detected_boxes(&merged, &boxes_to_process[i]);
                     boxes_to_process.remove(i);
                     merged_any = true;
                 } else {
@@ -423,6 +1034,71 @@ impl Det {
         result
     }
 
+    /// 合并来自不同分块的重复或被分块边界截断的文本框
+    ///
+    /// Merge boxes from different tiles that are duplicates or were cut by a tile seam
+    fn merge_tile_boxes(boxes: Vec<(usize, DetectedBox)>) -> Vec<DetectedBox> {
+        fn find(parent: &mut [usize], i: usize) -> usize {
+            let mut root = i;
+            while parent[root] != root {
+                root = parent[root];
+            }
+            parent[i] = root;
+            root
+        }
+
+        // 并查集：同一分块内的框互不合并，保持单图检测的结果
+        let mut parent: Vec<usize> = (0..boxes.len()).collect();
+        for i in 0..boxes.len() {
+            for j in (i + 1)..boxes.len() {
+                if boxes[i].0 != boxes[j].0
+                    && Self::is_seam_duplicate(&boxes[i].1.rect, &boxes[j].1.rect)
+                {
+                    let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+                    parent[a.max(b)] = a.min(b);
+                }
+            }
+        }
+
+        let mut merged: Vec<Option<DetectedBox>> = vec![None; boxes.len()];
+        for (i, (_, detected)) in boxes.into_iter().enumerate() {
+            let root = find(&mut parent, i);
+            merged[root] = Some(match merged[root].take() {
+                Some(current) => DetectedBox {
+                    quad: current.quad.union(&detected.quad),
+                    ..Self::merge_detected_boxes(&current, &detected)
+                },
+                None => detected,
+            });
+        }
+
+        merged.into_iter().flatten().collect()
+    }
+
+    /// 判断两个来自不同分块的边界框是否为同一文本：大部分互相包含，或沿文本方向相交且
+    /// 在垂直于文本的方向上基本对齐
+    ///
+    /// Determine whether two boxes from different tiles belong to the same text: they mostly
+    /// contain each other, or they intersect along the text direction and are aligned across it
+    fn is_seam_duplicate(a: &Rect, b: &Rect) -> bool {
+        let Some(inter) = a.intersect(*b) else {
+            return false;
+        };
+
+        let area = |r: &Rect| (r.width() * r.height()) as f32;
+        if area(&inter) >= 0.5 * area(a).min(area(b)) {
+            return true;
+        }
+
+        let union_w = a.right().max(b.right()) - a.left().min(b.left());
+        let union_h = a.bottom().max(b.bottom()) - a.top().min(b.top());
+        if union_w >= union_h {
+            inter.height() as f32 >= 0.5 * a.height().min(b.height()) as f32
+        } else {
+            inter.width() as f32 >= 0.5 * a.width().min(b.width()) as f32
+        }
+    }
+
     /// 判断两个边界框是否左右重叠(考虑阈值)，忽略上下重叠
     ///
     /// Determine whether two bounding boxes overlap horizontally (considering threshold), ignoring vertical overlap
@@ -463,6 +1139,26 @@ impl Det {
         horizontal_overlap && vertical_close
     }
 
+    /// 合并两个检测框，得分按面积加权平均
+    ///
+    /// Merge two detected boxes, averaging their scores weighted by area
+    fn merge_detected_boxes(a: &DetectedBox, b: &DetectedBox) -> DetectedBox {
+        let rect = Self::merge_boxes(&a.rect, &b.rect);
+        let area_a = (a.rect.width() * a.rect.height()) as f32;
+        let area_b = (b.rect.width() * b.rect.height()) as f32;
+        let score = if area_a + area_b > 0.0 {
+            (a.score * area_a + b.score * area_b) / (area_a + area_b)
+        } else {
+            a.score.max(b.score)
+        };
+
+        DetectedBox {
+            rect,
+            quad: TextQuad::from_rect(&rect),
+            score,
+        }
+    }
+
     /// 合并两个边界框，保持高度的独立性
     ///
     /// Merge two bounding boxes, maintaining height independence
@@ -506,29 +1202,16 @@ impl Det {
             y_max = y_max.max(p.y);
         }
 
-        let width = (x_max - x_min) as u32;
-        let height = (y_max - y_min) as u32;
+        let width = x_max - x_min;
+        let height = y_max - y_min;
 
-        // 使用常量判断最小尺寸
-        if width <= Self::MIN_BOX_SIZE || height <= Self::MIN_BOX_SIZE {
+        // 单像素或单行/单列轮廓无法构成有效矩形
+        if width == 0 || height == 0 {
             return None;
         }
 
         Some(Rect::at(x_min as i32, y_min as i32).of_size(width, height))
     }
-
-    #[inline]
-    /// 计算填充长度
-    ///
-    /// Calculate the padding length
-    const fn get_pad_length(length: u32) -> u32 {
-        let i = length % 32;
-        if i == 0 {
-            length
-        } else {
-            length + 32 - i
-        }
-    }
 }
 
 impl Drop for Det {
@@ -538,3 +1221,92 @@ impl Drop for Det {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limited_size() {
+        let options = DetOptions::default();
+        assert_eq!(options.limited_size(6000, 4000), None);
+
+        let options = options.with_limit_side_len(960, LimitType::Max);
+        assert_eq!(options.limited_size(6000, 4000), Some((960, 640)));
+        assert_eq!(options.limited_size(800, 600), None);
+
+        let options = options.with_limit_side_len(736, LimitType::Min);
+        assert_eq!(options.limited_size(460, 200), Some((1693, 736)));
+        assert_eq!(options.limited_size(6000, 4000), None);
+    }
+
+    #[test]
+    fn test_bounding_rect_degenerate() {
+        let speck = [Point::new(3, 4)];
+        assert_eq!(Det::bounding_rect(&speck), None);
+
+        let column = [Point::new(3, 4), Point::new(3, 9)];
+        assert_eq!(Det::bounding_rect(&column), None);
+
+        let block = [Point::new(3, 4), Point::new(8, 9)];
+        assert_eq!(
+            Det::bounding_rect(&block),
+            Some(Rect::at(3, 4).of_size(5, 5))
+        );
+    }
+
+    #[test]
+    fn test_tile_offsets() {
+        let tiling = TileOptions {
+            tile_size: 1000,
+            overlap: 200,
+        };
+        assert_eq!(tiling.offsets(800), vec![0]);
+        assert_eq!(tiling.offsets(1000), vec![0]);
+        assert_eq!(tiling.offsets(2500), vec![0, 800, 1500]);
+    }
+
+    #[test]
+    fn test_translate_clips_rect() {
+        let rect = Rect::at(10, 20).of_size(50, 30);
+        let detected = DetectedBox {
+            rect,
+            quad: TextQuad::from_rect(&rect),
+            score: 0.9,
+        };
+
+        let moved = detected.clone().translate(5, 5, 1000, 1000);
+        assert_eq!(moved.rect, Rect::at(15, 25).of_size(50, 30));
+
+        let clipped = detected.translate(40, 60, 80, 100);
+        assert_eq!(clipped.rect, Rect::at(50, 80).of_size(30, 20));
+    }
+
+    #[test]
+    fn test_merge_tile_boxes() {
+        let detected = |left: i32, top: i32, width: u32, height: u32| {
+            let rect = Rect::at(left, top).of_size(width, height);
+            DetectedBox {
+                rect,
+                quad: TextQuad::from_rect(&rect),
+                score: 0.9,
+            }
+        };
+
+        let boxes = vec![
+            // 被分块边界截断的同一行文本
+            (0, detected(700, 100, 300, 30)),
+            (1, detected(800, 102, 400, 28)),
+            // 同一分块内相邻的两行不应合并
+            (0, detected(100, 300, 200, 30)),
+            (0, detected(100, 325, 200, 30)),
+            // 上下相邻但来自不同分块的两行不应合并
+            (1, detected(850, 300, 200, 30)),
+            (2, detected(850, 326, 200, 30)),
+        ];
+
+        let merged = Det::merge_tile_boxes(boxes);
+        assert_eq!(merged.len(), 5);
+        assert_eq!(merged[0].rect, Rect::at(700, 100).of_size(500, 30));
+    }
+}
@@ -1,8 +1,11 @@
-use image::{DynamicImage, GenericImageView, ImageBuffer};
+use image::{DynamicImage, GenericImageView, ImageBuffer, Rgb, RgbImage};
+use imageproc::geometric_transformations::{warp_into, Interpolation, Projection};
 use imageproc::rect::Rect;
 use rayon::prelude::*;
 use std::sync::Arc;
 
+use crate::geometry::{self, TextQuad};
+
 /// 高效图像裁剪工具
 /// Efficient image cropping utilities
 pub struct EfficientCropper;
@@ -201,6 +204,70 @@ impl EfficientCropper {
 
         results.into_iter().map(|r| r.unwrap()).collect()
     }
+
+    /// 透视校正裁剪：将四边形区域变换为水平方向的矩形文本行图像
+    ///
+    /// 与 PaddleOCR 的 `get_rotate_crop_image` 一致，输出宽高取自四边形的边长，
+    /// 高宽比不小于1.5的结果会逆时针旋转90度。
+    ///
+    /// Perspective-corrected crop: warp a quadrilateral region into an upright, horizontal text line image
+    ///
+    /// Matches PaddleOCR's `get_rotate_crop_image`: the output size is taken from the quad's edge
+    /// lengths, and results with a height/width ratio of at least 1.5 are rotated 90 degrees counter-clockwise.
+    pub fn perspective_crop(image: &ImageRef, quad: &TextQuad) -> DynamicImage {
+        let (img_w, img_h) = image.dimensions();
+        let crop_w = quad.width().round().max(1.0) as u32;
+        let crop_h = quad.height().round().max(1.0) as u32;
+
+        // 只对四边形的外接区域做变换，避免处理整幅图像
+        let bounds = quad.bounding_rect(img_w, img_h);
+        let region = Self::smart_crop(image, &bounds).to_rgb8();
+
+        let (ox, oy) = (bounds.left() as f32, bounds.top() as f32);
+        let src = quad.points.map(|p| (p.x - ox, p.y - oy));
+        let dst = [
+            (0.0, 0.0),
+            (crop_w as f32, 0.0),
+            (crop_w as f32, crop_h as f32),
+            (0.0, crop_h as f32),
+        ];
+
+        let mut warped = RgbImage::new(crop_w, crop_h);
+        match Projection::from_control_points(src, dst) {
+            Some(projection) => warp_into(
+                &region,
+                &projection,
+                Interpolation::Bilinear,
+                Rgb([0, 0, 0]),
+                &mut warped,
+            ),
+            // 退化的四边形无法求解透视变换，退回到外接矩形裁剪
+            None => return DynamicImage::ImageRgb8(region),
+        }
+
+        let warped = DynamicImage::ImageRgb8(warped);
+        if geometry::is_vertical(crop_w as f32, crop_h as f32) {
+            warped.rotate270()
+        } else {
+            warped
+        }
+    }
+
+    /// 批量透视校正裁剪
+    /// Batch perspective-corrected cropping
+    pub fn batch_perspective_crop(image: &ImageRef, quads: &[TextQuad]) -> Vec<DynamicImage> {
+        if quads.len() < 4 {
+            quads
+                .iter()
+                .map(|quad| Self::perspective_crop(image, quad))
+                .collect()
+        } else {
+            quads
+                .par_iter()
+                .map(|quad| Self::perspective_crop(image, quad))
+                .collect()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -248,4 +315,20 @@ mod tests {
         assert_eq!(results[1].dimensions(), (25, 25));
         assert_eq!(results[2].dimensions(), (30, 30));
     }
+
+    #[test]
+    fn test_perspective_crop() {
+        let image = create_test_image();
+        let image_ref = ImageRef::from(image);
+
+        // 轴对齐的四边形应与普通裁剪的尺寸一致
+        let quad = TextQuad::from_rect(&Rect::at(10, 20).of_size(40, 12));
+        let cropped = EfficientCropper::perspective_crop(&image_ref, &quad);
+        assert_eq!(cropped.dimensions(), (40, 12));
+
+        // 竖直的窄长区域会被旋转为水平方向
+        let quad = TextQuad::from_rect(&Rect::at(10, 10).of_size(10, 60));
+        let cropped = EfficientCropper::perspective_crop(&image_ref, &quad);
+        assert_eq!(cropped.dimensions(), (60, 10));
+    }
 }
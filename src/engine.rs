@@ -1,10 +1,22 @@
-use crate::{Det, OcrError, OcrResult, Rec};
+use crate::bundle::ModelBundle;
+use crate::efficient_cropping::{EfficientCropper, ImageRef};
+use crate::geometry;
+use crate::lm::LanguageModel;
+use crate::model_dir::ModelDir;
+use crate::orientation::rotate_upright;
+use crate::result::{self, LineRecognition};
+use crate::{
+    CharFilter, Cls, CtcDecoder, CtcMode, Det, DetOptions, DetectedBox, DocOri, ModelProfile,
+    OcrError, OcrResult, PageResult, ProbabilityMap, Rec, RecognizeOptions, RuntimeOptions,
+    ScoreAggregation, TextLine, TextQuad,
+};
 
-use crossbeam_channel::{unbounded, Receiver, Sender};
+use crossbeam_channel::{bounded, unbounded, Receiver, Sender};
 use image::DynamicImage;
 use imageproc::rect::Rect;
 use std::{
-    path::Path,
+    fmt,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
     thread,
 };
@@ -54,6 +66,16 @@ pub enum OcrRequest {
         /// Result sender channel
         result_sender: Sender<OcrResult<Vec<Rect>>>,
     },
+    /// 获取文本区域旋转四边形框请求
+    /// Get text region rotated quadrilaterals request
+    GetTextQuads {
+        /// 输入图像
+        /// Input image
+        image: DynamicImage,
+        /// 结果发送通道
+        /// Result sender channel
+        result_sender: Sender<OcrResult<Vec<TextQuad>>>,
+    },
     /// 获取文本区域图像请求
     /// Get text region images request
     GetTextImages {
@@ -84,11 +106,439 @@ pub enum OcrRequest {
         /// Result sender channel
         result_sender: Sender<OcrResult<Vec<String>>>,
     },
+    /// 使用旋转四边形框和透视校正获取文本区域图像请求
+    /// Get text region images using rotated quads and perspective correction
+    GetTextImagesRotated {
+        /// 输入图像
+        /// Input image
+        image: DynamicImage,
+        /// 结果发送通道
+        /// Result sender channel
+        result_sender: Sender<OcrResult<Vec<DynamicImage>>>,
+    },
+    /// 使用旋转四边形框和透视校正的完整OCR处理请求
+    /// Full OCR processing request with rotated quads and perspective correction
+    ProcessOcrRotated {
+        /// 输入图像
+        /// Input image
+        image: DynamicImage,
+        /// 结果发送通道
+        /// Result sender channel
+        result_sender: Sender<OcrResult<Vec<String>>>,
+    },
+    /// 获取带检测得分的文本框请求
+    /// Get text boxes with detection scores request
+    GetTextBoxes {
+        /// 输入图像
+        /// Input image
+        image: DynamicImage,
+        /// 结果发送通道
+        /// Result sender channel
+        result_sender: Sender<OcrResult<Vec<DetectedBox>>>,
+    },
+    /// 获取文本概率图请求
+    /// Get text probability map request
+    GetProbabilityMap {
+        /// 输入图像
+        /// Input image
+        image: DynamicImage,
+        /// 结果发送通道
+        /// Result sender channel
+        result_sender: Sender<OcrResult<ProbabilityMap>>,
+    },
+    /// 返回详细结果的完整OCR处理请求
+    /// Full OCR processing request returning detailed results
+    ProcessOcrDetailed {
+        /// 输入图像
+        /// Input image
+        image: DynamicImage,
+        /// 本次请求的识别选项
+        /// Recognition options of this request
+        options: RecognizeOptions,
+        /// 结果发送通道
+        /// Result sender channel
+        result_sender: Sender<OcrResult<PageResult>>,
+    },
     /// 关闭引擎请求
     /// Shutdown engine request
     Shutdown,
 }
 
+/// 模型来源：文件路径或内存中的模型数据
+///
+/// Model source: a file path or model data in memory
+#[derive(Clone)]
+pub enum ModelSource {
+    /// 模型文件路径
+    /// Model file path
+    File(PathBuf),
+    /// 内存中的模型数据
+    /// Model data in memory
+    Bytes(Vec<u8>),
+}
+
+impl fmt::Debug for ModelSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path) => f.debug_tuple("File").field(path).finish(),
+            Self::Bytes(data) => write!(f, "Bytes({} bytes)", data.len()),
+        }
+    }
+}
+
+impl From<PathBuf> for ModelSource {
+    fn from(path: PathBuf) -> Self {
+        Self::File(path)
+    }
+}
+
+impl From<&Path> for ModelSource {
+    fn from(path: &Path) -> Self {
+        Self::File(path.to_path_buf())
+    }
+}
+
+impl From<Vec<u8>> for ModelSource {
+    fn from(data: Vec<u8>) -> Self {
+        Self::Bytes(data)
+    }
+}
+
+impl From<&[u8]> for ModelSource {
+    fn from(data: &[u8]) -> Self {
+        Self::Bytes(data.to_vec())
+    }
+}
+
+/// OCR引擎配置
+///
+/// OCR engine configuration
+#[derive(Debug, Clone)]
+pub struct OcrEngineConfig {
+    /// 文本框边界扩展大小
+    /// Text box border extension size
+    pub rect_border_size: u32,
+    /// 是否合并相邻文本框
+    /// Whether to merge adjacent text boxes
+    pub merge_boxes: bool,
+    /// 文本框合并阈值
+    /// Threshold for merging text boxes
+    pub merge_threshold: i32,
+    /// 检测后处理参数
+    /// Detection postprocessing options
+    pub det_options: DetOptions,
+    /// 是否为旋转文本框同时返回轮廓多边形（`TextQuad::polygon`）
+    /// Whether to also return the contour polygon (`TextQuad::polygon`) with each rotated
+    /// text box
+    pub det_polygons: bool,
+    /// 可选的文本行方向分类模型，设置后会在识别前校正上下颠倒的文本行
+    /// Optional text line direction classifier; when set, upside-down lines are corrected
+    /// before recognition
+    pub cls_model: Option<ModelSource>,
+    /// 方向分类的旋转置信度阈值
+    /// Rotation confidence threshold of the direction classifier
+    pub cls_thresh: f32,
+    /// 是否在完整OCR处理前自动校正页面方向（0°/90°/180°/270°）
+    /// Whether to correct the page orientation (0°/90°/180°/270°) before full OCR processing
+    pub auto_rotate: bool,
+    /// 可选的文档方向分类模型；未设置时使用基于文本框形状和识别置信度的启发式方法
+    /// Optional document orientation classifier; without it a heuristic based on box shapes
+    /// and recognition confidence is used
+    pub doc_ori_model: Option<ModelSource>,
+    /// 批量识别时每次推理的最大文本行数量
+    /// Maximum number of text lines per inference call in batched recognition
+    pub rec_batch_size: usize,
+    /// 识别输入的宽度分档，为空时不分档
+    /// Recognition input width buckets; empty disables bucketing
+    pub rec_width_buckets: Vec<u32>,
+    /// 识别结果的 CTC 解码方式
+    /// CTC decoding mode of recognition results
+    pub rec_ctc_mode: CtcMode,
+    /// 束搜索的束宽
+    /// Beam width of beam search
+    pub rec_beam_width: usize,
+    /// 束搜索使用的可选词典或语言模型
+    /// Optional lexicon or language model used by beam search
+    pub rec_language_model: Option<Arc<dyn LanguageModel>>,
+    /// 语言模型得分的权重
+    /// Weight of the language model score
+    pub rec_lm_weight: f32,
+    /// 识别结果的字符过滤规则
+    /// Character filter of recognition results
+    pub rec_char_filter: CharFilter,
+    /// 常规字符的最小置信度；标准贪心和束搜索解码将低于该值的字符标记为不确定，
+    /// 启发式解码则将其丢弃
+    /// Minimum confidence of regular characters; greedy and beam search decoding flag
+    /// characters below it as uncertain, while heuristic decoding drops them
+    pub rec_min_score: f32,
+    /// 由字符置信度计算文本行和词置信度的方式
+    /// How line and word confidences are computed from character confidences
+    pub rec_score_aggregation: ScoreAggregation,
+    /// 检测和识别模型的预处理和输出配置，须与模型的 PaddleOCR 版本一致
+    /// Preprocessing and output profile of the detection and recognition models; must match
+    /// the PaddleOCR release of the models
+    pub model_profile: ModelProfile,
+    /// 所有模型共用的推理运行时设置
+    /// Inference runtime settings shared by all models
+    pub runtime: RuntimeOptions,
+}
+
+impl Default for OcrEngineConfig {
+    fn default() -> Self {
+        Self {
+            rect_border_size: Det::RECT_BORDER_SIZE,
+            merge_boxes: false,
+            merge_threshold: Det::DEFAULT_MERGE_THRESHOLD,
+            det_options: DetOptions::default(),
+            det_polygons: false,
+            cls_model: None,
+            cls_thresh: Cls::DEFAULT_THRESH,
+            auto_rotate: false,
+            doc_ori_model: None,
+            rec_batch_size: Rec::BATCH_SIZE_DEFAULT,
+            rec_width_buckets: Rec::WIDTH_BUCKETS_DEFAULT.to_vec(),
+            rec_ctc_mode: CtcMode::default(),
+            rec_beam_width: CtcDecoder::BEAM_WIDTH_DEFAULT,
+            rec_language_model: None,
+            rec_lm_weight: CtcDecoder::LM_WEIGHT_DEFAULT,
+            rec_char_filter: CharFilter::default(),
+            rec_min_score: CtcDecoder::MIN_SCORE_DEFAULT,
+            rec_score_aggregation: ScoreAggregation::default(),
+            model_profile: ModelProfile::default(),
+            runtime: RuntimeOptions::default(),
+        }
+    }
+}
+
+impl OcrEngineConfig {
+    /// 设置文本框边界扩展大小
+    ///
+    /// Set the text box border extension size
+    pub fn with_rect_border_size(mut self, rect_border_size: u32) -> Self {
+        self.rect_border_size = rect_border_size;
+        self
+    }
+
+    /// 设置是否合并相邻文本框
+    ///
+    /// Set whether to merge adjacent text boxes
+    pub fn with_merge_boxes(mut self, merge_boxes: bool) -> Self {
+        self.merge_boxes = merge_boxes;
+        self
+    }
+
+    /// 设置文本框合并阈值
+    ///
+    /// Set the threshold for merging text boxes
+    pub fn with_merge_threshold(mut self, merge_threshold: i32) -> Self {
+        self.merge_threshold = merge_threshold;
+        self
+    }
+
+    /// 设置检测后处理参数
+    ///
+    /// Set the detection postprocessing options
+    pub fn with_det_options(mut self, det_options: DetOptions) -> Self {
+        self.det_options = det_options;
+        self
+    }
+
+    /// 设置是否为旋转文本框同时返回轮廓多边形
+    ///
+    /// Set whether to also return the contour polygon with each rotated text box
+    pub fn with_polygons(mut self, det_polygons: bool) -> Self {
+        self.det_polygons = det_polygons;
+        self
+    }
+
+    /// 启用分块检测，适用于超大图像
+    ///
+    /// Enable tiled detection for very large images
+    pub fn with_tiling(mut self, tile_size: u32, overlap: u32) -> Self {
+        self.det_options = self.det_options.with_tiling(tile_size, overlap);
+        self
+    }
+
+    /// 启用文本行方向分类（0°/180°）
+    ///
+    /// Enable text line direction classification (0°/180°)
+    pub fn with_cls_model(mut self, cls_model: impl Into<ModelSource>) -> Self {
+        self.cls_model = Some(cls_model.into());
+        self
+    }
+
+    /// 设置方向分类的旋转置信度阈值
+    ///
+    /// Set the rotation confidence threshold of the direction classifier
+    pub fn with_cls_thresh(mut self, cls_thresh: f32) -> Self {
+        self.cls_thresh = cls_thresh;
+        self
+    }
+
+    /// 设置是否自动校正页面方向
+    ///
+    /// Set whether to correct the page orientation automatically
+    pub fn with_auto_rotate(mut self, auto_rotate: bool) -> Self {
+        self.auto_rotate = auto_rotate;
+        self
+    }
+
+    /// 使用文档方向分类模型校正页面方向，同时启用自动校正
+    ///
+    /// Use a document orientation classifier for page orientation, which also enables auto-rotation
+    pub fn with_doc_ori_model(mut self, doc_ori_model: impl Into<ModelSource>) -> Self {
+        self.doc_ori_model = Some(doc_ori_model.into());
+        self.auto_rotate = true;
+        self
+    }
+
+    /// 设置批量识别时每次推理的最大文本行数量
+    ///
+    /// Set the maximum number of text lines per inference call in batched recognition
+    pub fn with_rec_batch_size(mut self, rec_batch_size: usize) -> Self {
+        self.rec_batch_size = rec_batch_size;
+        self
+    }
+
+    /// 设置识别输入的宽度分档
+    ///
+    /// Set the recognition input width buckets
+    pub fn with_rec_width_buckets(mut self, rec_width_buckets: Vec<u32>) -> Self {
+        self.rec_width_buckets = rec_width_buckets;
+        self
+    }
+
+    /// 设置识别结果的 CTC 解码方式
+    ///
+    /// Set the CTC decoding mode of recognition results
+    pub fn with_ctc_mode(mut self, rec_ctc_mode: CtcMode) -> Self {
+        self.rec_ctc_mode = rec_ctc_mode;
+        self
+    }
+
+    /// 使用给定束宽的束搜索解码识别结果
+    ///
+    /// Decode recognition results with beam search of the given beam width
+    pub fn with_beam_search(mut self, rec_beam_width: usize) -> Self {
+        self.rec_ctc_mode = CtcMode::BeamSearch;
+        self.rec_beam_width = rec_beam_width;
+        self
+    }
+
+    /// 设置束搜索使用的词典或语言模型及其权重，并启用束搜索解码
+    ///
+    /// Set the lexicon or language model used by beam search together with its weight, and
+    /// enable beam search decoding
+    pub fn with_language_model(mut self, lm: Arc<dyn LanguageModel>, lm_weight: f32) -> Self {
+        self.rec_ctc_mode = CtcMode::BeamSearch;
+        self.rec_language_model = Some(lm);
+        self.rec_lm_weight = lm_weight;
+        self
+    }
+
+    /// 设置识别结果的字符过滤规则，只输出允许的字符
+    ///
+    /// Set the character filter of recognition results so that only allowed characters are emitted
+    pub fn with_char_filter(mut self, rec_char_filter: CharFilter) -> Self {
+        self.rec_char_filter = rec_char_filter;
+        self
+    }
+
+    /// 设置常规字符的最小置信度，低于该值的字符被标记为不确定（启发式解码中被丢弃）
+    ///
+    /// Set the minimum confidence of regular characters; characters below it are flagged as
+    /// uncertain (or dropped by heuristic decoding)
+    pub fn with_min_score(mut self, rec_min_score: f32) -> Self {
+        self.rec_min_score = rec_min_score;
+        self
+    }
+
+    /// 设置由字符置信度计算文本行和词置信度的方式
+    ///
+    /// Set how line and word confidences are computed from character confidences
+    pub fn with_score_aggregation(mut self, rec_score_aggregation: ScoreAggregation) -> Self {
+        self.rec_score_aggregation = rec_score_aggregation;
+        self
+    }
+
+    /// 设置检测和识别模型的预处理和输出配置
+    ///
+    /// Set the preprocessing and output profile of the detection and recognition models
+    pub fn with_model_profile(mut self, model_profile: ModelProfile) -> Self {
+        self.model_profile = model_profile;
+        self
+    }
+
+    /// 设置推理运行时，例如限制每个引擎的线程数或改用更高的精度
+    ///
+    /// Set the inference runtime, e.g. to cap the threads of each engine or use a higher
+    /// precision
+    pub fn with_runtime(mut self, runtime: RuntimeOptions) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    /// 将配置应用到文本识别器
+    ///
+    /// Apply the configuration to a text recognizer
+    fn apply_to_rec(&self, rec: Rec) -> Rec {
+        let rec = rec
+            .with_batch_size(self.rec_batch_size)
+            .with_width_buckets(self.rec_width_buckets.clone())
+            .with_ctc_mode(self.rec_ctc_mode)
+            .with_beam_width(self.rec_beam_width)
+            .with_lm_weight(self.rec_lm_weight)
+            .with_char_filter(self.rec_char_filter.clone())
+            .with_min_score(self.rec_min_score)
+            .with_score_aggregation(self.rec_score_aggregation)
+            .with_profile(self.model_profile)
+            .with_runtime(self.runtime);
+        match &self.rec_language_model {
+            Some(lm) => rec.with_language_model(lm.clone()),
+            None => rec,
+        }
+    }
+
+    /// 按配置加载可选的文档方向分类模型
+    ///
+    /// Load the optional document orientation classifier from the configuration
+    fn load_doc_ori(&self) -> OcrResult<Option<DocOri>> {
+        let doc_ori = match &self.doc_ori_model {
+            Some(ModelSource::File(path)) => DocOri::from_file(path)?,
+            Some(ModelSource::Bytes(data)) => DocOri::from_bytes(data)?,
+            None => return Ok(None),
+        };
+        Ok(Some(doc_ori.with_runtime(self.runtime)))
+    }
+
+    /// 按配置加载可选的方向分类模型
+    ///
+    /// Load the optional direction classifier from the configuration
+    fn load_cls(&self) -> OcrResult<Option<Cls>> {
+        let cls = match &self.cls_model {
+            Some(ModelSource::File(path)) => Cls::from_file(path)?,
+            Some(ModelSource::Bytes(data)) => Cls::from_bytes(data)?,
+            None => return Ok(None),
+        };
+        Ok(Some(
+            cls.with_thresh(self.cls_thresh).with_runtime(self.runtime),
+        ))
+    }
+
+    /// 将配置应用到文本检测器
+    ///
+    /// Apply the configuration to a text detector
+    fn apply_to_det(&self, det: Det) -> Det {
+        det.with_rect_border_size(self.rect_border_size)
+            .with_merge_boxes(self.merge_boxes)
+            .with_merge_threshold(self.merge_threshold)
+            .with_options(self.det_options)
+            .with_polygons(self.det_polygons)
+            .with_profile(self.model_profile)
+            .with_runtime(self.runtime)
+    }
+}
+
 /// 线程安全的OCR引擎管理器
 ///
 /// Thread-safe OCR engine manager
@@ -127,33 +577,82 @@ impl OcrEngine {
         merge_boxes: bool,
         merge_threshold: i32,
     ) -> OcrResult<Self> {
-        // 创建通信通道
-        let (tx, rx) = unbounded();
+        Self::new_with_options(
+            det_model_path,
+            rec_model_path,
+            keys_path,
+            OcrEngineConfig::default()
+                .with_rect_border_size(rect_border_size)
+                .with_merge_boxes(merge_boxes)
+                .with_merge_threshold(merge_threshold),
+        )
+    }
 
-        // 创建工作线程，该线程将持有OCR模型
-        let worker_handle = thread::spawn({
-            let det_path = det_model_path.as_ref().to_path_buf();
-            let rec_path = rec_model_path.as_ref().to_path_buf();
-            let keys = keys_path.as_ref().to_path_buf();
-
-            move || match Self::run_worker(
-                det_path,
-                rec_path,
-                keys,
-                rx,
-                rect_border_size,
-                merge_boxes,
-                merge_threshold,
-            ) {
-                Ok(_) => {}
-                Err(e) => eprintln!("OCR worker error: {}", e),
-            }
-        });
+    /// 使用完整的引擎配置创建并启动OCR引擎实例
+    ///
+    /// Create and start a new OCR engine instance with a full engine configuration
+    pub fn new_with_options(
+        det_model_path: impl AsRef<Path>,
+        rec_model_path: impl AsRef<Path>,
+        keys_path: impl AsRef<Path>,
+        config: OcrEngineConfig,
+    ) -> OcrResult<Self> {
+        let det_path = det_model_path.as_ref().to_path_buf();
+        let rec_path = rec_model_path.as_ref().to_path_buf();
+        let keys = keys_path.as_ref().to_path_buf();
 
-        Ok(Self {
-            request_sender: tx,
-            worker_handle: Some(worker_handle),
-        })
+        Self::spawn_worker(move || Self::load_worker(det_path, rec_path, keys, config))
+    }
+
+    /// 从模型目录创建并启动OCR引擎实例，见 [`ModelDir`]
+    ///
+    /// Create and start a new OCR engine instance from a model directory, see [`ModelDir`]
+    pub fn from_dir(dir: impl AsRef<Path>) -> OcrResult<Self> {
+        Self::from_dir_with_options(dir, OcrEngineConfig::default())
+    }
+
+    /// 从模型目录和引擎配置创建OCR引擎实例，目录中的模型配置和清单参数会覆盖 `config` 中的设置
+    ///
+    /// Create an OCR engine instance from a model directory and an engine configuration. The
+    /// directory's model profile and manifest options override those in `config`
+    pub fn from_dir_with_options(
+        dir: impl AsRef<Path>,
+        config: OcrEngineConfig,
+    ) -> OcrResult<Self> {
+        let model_dir = ModelDir::open(dir)?;
+        let config = model_dir.apply_to(config);
+        Self::new_with_options(
+            &model_dir.det_model,
+            &model_dir.rec_model,
+            &model_dir.keys,
+            config,
+        )
+    }
+
+    /// 从单文件模型包创建并启动OCR引擎实例，校验失败时返回错误
+    ///
+    /// Create and start a new OCR engine instance from a single-file model bundle, failing
+    /// when a checksum does not match
+    pub fn from_bundle(bundle_path: impl AsRef<Path>) -> OcrResult<Self> {
+        Self::from_bundle_with_options(bundle_path, OcrEngineConfig::default())
+    }
+
+    /// 从单文件模型包和引擎配置创建OCR引擎实例，模型包中的模型配置会覆盖 `config` 中的设置，
+    /// 包含方向分类模型且 `config` 未指定时一并启用
+    ///
+    /// Create an OCR engine instance from a single-file model bundle and an engine
+    /// configuration. The bundle's model profile overrides the one in `config`, and its
+    /// direction classifier is used unless `config` specifies one
+    pub fn from_bundle_with_options(
+        bundle_path: impl AsRef<Path>,
+        config: OcrEngineConfig,
+    ) -> OcrResult<Self> {
+        let bundle = ModelBundle::from_file(bundle_path)?;
+        let mut config = config.with_model_profile(bundle.profile);
+        if let Some(cls) = bundle.cls.filter(|_| config.cls_model.is_none()) {
+            config = config.with_cls_model(cls);
+        }
+        Self::new_with_options_and_bytes(&bundle.det, &bundle.rec, &bundle.keys, config)
     }
 
     /// 创建并启动一个带有自定义配置和字节数据的OCR引擎实例
@@ -167,29 +666,64 @@ impl OcrEngine {
         merge_boxes: bool,
         merge_threshold: i32,
     ) -> OcrResult<Self> {
-        // 创建通信通道
-        let (tx, rx) = unbounded();
+        Self::new_with_options_and_bytes(
+            det_model_data,
+            rec_model_data,
+            keys_data,
+            OcrEngineConfig::default()
+                .with_rect_border_size(rect_border_size)
+                .with_merge_boxes(merge_boxes)
+                .with_merge_threshold(merge_threshold),
+        )
+    }
 
+    /// 使用完整的引擎配置和字节数据创建并启动OCR引擎实例
+    ///
+    /// Create and start a new OCR engine instance with a full engine configuration and byte data
+    pub fn new_with_options_and_bytes(
+        det_model_data: &[u8],
+        rec_model_data: &[u8],
+        keys_data: &[u8],
+        config: OcrEngineConfig,
+    ) -> OcrResult<Self> {
         // 克隆字节数据，准备传递给工作线程
         let det_data = det_model_data.to_vec();
         let rec_data = rec_model_data.to_vec();
         let keys = keys_data.to_vec();
 
+        Self::spawn_worker(move || Self::load_worker_with_bytes(det_data, rec_data, keys, config))
+    }
+
+    /// 创建工作线程并在其中加载模型，模型加载完成后才返回，加载失败时返回对应错误
+    ///
+    /// Spawn the worker thread and load the models on it; returns once loading has finished,
+    /// with the loading error if it failed
+    fn spawn_worker(load: impl FnOnce() -> OcrResult<Worker> + Send + 'static) -> OcrResult<Self> {
+        // 创建通信通道
+        let (tx, rx) = unbounded();
+        let (ready_tx, ready_rx) = bounded(1);
+
         // 创建工作线程，该线程将持有OCR模型
-        let worker_handle = thread::spawn(move || {
-            match Self::run_worker_with_bytes(
-                det_data,
-                rec_data,
-                keys,
-                rx,
-                rect_border_size,
-                merge_boxes,
-                merge_threshold,
-            ) {
-                Ok(_) => {}
-                Err(e) => eprintln!("OCR worker error: {}", e),
+        let worker_handle = thread::spawn(move || match load() {
+            Ok(worker) => {
+                let _ = ready_tx.send(Ok(()));
+                worker.handle_requests(rx);
             }
+            Err(e) => {
+                let _ = ready_tx.send(Err(e));
+            }
+        });
+
+        // 等待模型加载结果
+        let ready = ready_rx.recv().unwrap_or_else(|_| {
+            Err(OcrError::EngineError(
+                "OCR engine worker thread terminated while loading models".to_string(),
+            ))
         });
+        if let Err(e) = ready {
+            let _ = worker_handle.join();
+            return Err(e);
+        }
 
         Ok(Self {
             request_sender: tx,
@@ -243,6 +777,29 @@ impl OcrEngine {
         })?
     }
 
+    /// 获取文本区域的旋转四边形框
+    ///
+    /// Get text region rotated quadrilaterals
+    pub fn get_text_quads(&self, image: &DynamicImage) -> OcrResult<Vec<TextQuad>> {
+        // 创建结果通道
+        let (result_tx, result_rx) = unbounded();
+
+        // 发送请求
+        self.request_sender
+            .send(OcrRequest::GetTextQuads {
+                image: image.clone(),
+                result_sender: result_tx,
+            })
+            .map_err(|_| {
+                OcrError::EngineError("OCR engine worker thread has terminated".to_string())
+            })?;
+
+        // 等待结果
+        result_rx.recv().map_err(|_| {
+            OcrError::EngineError("Failed to receive result from worker thread".to_string())
+        })?
+    }
+
     /// 获取文本区域图像
     ///
     /// Get text region images
@@ -358,145 +915,212 @@ impl OcrEngine {
         })?
     }
 
-    /// 工作线程的主处理函数
+    /// 使用旋转四边形框和透视校正获取文本区域图像
     ///
-    /// Main processing function for the worker thread
-    fn run_worker(
-        det_model_path: impl AsRef<Path>,
-        rec_model_path: impl AsRef<Path>,
-        keys_path: impl AsRef<Path>,
-        receiver: Receiver<OcrRequest>,
-        rect_border_size: u32,
-        merge_boxes: bool,
-        merge_threshold: i32,
-    ) -> OcrResult<()> {
-        // 初始化模型，应用自定义配置
-        let mut det = Det::from_file(det_model_path)?
-            .with_rect_border_size(rect_border_size)
-            .with_merge_boxes(merge_boxes)
-            .with_merge_threshold(merge_threshold);
+    /// Get text region images using rotated quads and perspective correction
+    pub fn get_text_images_rotated(&self, image: &DynamicImage) -> OcrResult<Vec<DynamicImage>> {
+        // 创建结果通道
+        let (result_tx, result_rx) = unbounded();
 
-        let mut rec = Rec::from_file(rec_model_path, keys_path)?;
+        // 发送请求
+        self.request_sender
+            .send(OcrRequest::GetTextImagesRotated {
+                image: image.clone(),
+                result_sender: result_tx,
+            })
+            .map_err(|_| {
+                OcrError::EngineError("OCR engine worker thread has terminated".to_string())
+            })?;
 
-        // 处理请求循环
-        for request in receiver {
-            match request {
-                OcrRequest::DetectText {
-                    image,
-                    result_sender,
-                } => {
-                    let result = det.find_text_img(&image);
-                    // 发送结果，忽略接收端可能已关闭的错误
-                    let _ = result_sender.send(result);
-                }
-                OcrRequest::GetTextRects {
-                    image,
-                    result_sender,
-                } => {
-                    let result = det.find_text_rect(&image);
-                    let _ = result_sender.send(result);
-                }
-                OcrRequest::GetTextImages {
-                    image,
-                    result_sender,
-                } => {
-                    let result = det.find_text_img(&image);
-                    let _ = result_sender.send(result);
-                }
-                OcrRequest::RecognizeText {
-                    image,
-                    result_sender,
-                } => {
-                    let result = rec.predict_str(&image);
-                    let _ = result_sender.send(result);
-                }
-                OcrRequest::ProcessOcr {
-                    image,
-                    result_sender,
-                } => {
-                    // 先检测文本区域
-                    match det.find_text_img(&image) {
-                        Ok(text_images) => {
-                            // 识别每个文本区域
-                            let mut results = Vec::with_capacity(text_images.len());
-                            for text_img in text_images {
-                                match rec.predict_str(&text_img) {
-                                    Ok(text) => results.push(text),
-                                    Err(e) => {
-                                        let _ = result_sender.send(Err(e));
-                                        break;
-                                    }
-                                }
-                            }
-                            let _ = result_sender.send(Ok(results));
-                        }
-                        Err(e) => {
-                            let _ = result_sender.send(Err(e));
-                        }
-                    }
-                }
-                OcrRequest::GetTextImagesEfficient {
-                    image,
-                    result_sender,
-                } => {
-                    let result = det.find_text_img_efficient(&image);
-                    let _ = result_sender.send(result);
-                }
-                OcrRequest::ProcessOcrEfficient {
-                    image,
-                    result_sender,
-                } => {
-                    // 使用高效裁剪先检测文本区域
-                    match det.find_text_img_efficient(&image) {
-                        Ok(text_images) => {
-                            // 识别每个文本区域
-                            let mut results = Vec::with_capacity(text_images.len());
-                            for text_img in text_images {
-                                match rec.predict_str(&text_img) {
-                                    Ok(text) => results.push(text),
-                                    Err(e) => {
-                                        let _ = result_sender.send(Err(e));
-                                        break;
-                                    }
-                                }
-                            }
-                            let _ = result_sender.send(Ok(results));
-                        }
-                        Err(e) => {
-                            let _ = result_sender.send(Err(e));
-                        }
-                    }
-                }
-                OcrRequest::Shutdown => {
-                    // 收到关闭请求，退出循环
-                    break;
-                }
-            }
-        }
+        // 等待结果
+        result_rx.recv().map_err(|_| {
+            OcrError::EngineError("Failed to receive result from worker thread".to_string())
+        })?
+    }
 
-        Ok(())
+    /// 使用旋转四边形框和透视校正的完整OCR处理，适用于倾斜的文本
+    ///
+    /// Complete OCR processing using rotated quads and perspective correction, suited to skewed text
+    pub fn process_ocr_rotated(&self, image: DynamicImage) -> OcrResult<Vec<String>> {
+        // 创建结果通道
+        let (result_tx, result_rx) = unbounded();
+
+        // 发送请求
+        self.request_sender
+            .send(OcrRequest::ProcessOcrRotated {
+                image,
+                result_sender: result_tx,
+            })
+            .map_err(|_| {
+                OcrError::EngineError("OCR engine worker thread has terminated".to_string())
+            })?;
+
+        // 等待结果
+        result_rx.recv().map_err(|_| {
+            OcrError::EngineError("Failed to receive result from worker thread".to_string())
+        })?
+    }
+
+    /// 获取带检测得分的文本框
+    ///
+    /// Get text boxes with their detection scores
+    pub fn get_text_boxes(&self, image: &DynamicImage) -> OcrResult<Vec<DetectedBox>> {
+        // 创建结果通道
+        let (result_tx, result_rx) = unbounded();
+
+        // 发送请求
+        self.request_sender
+            .send(OcrRequest::GetTextBoxes {
+                image: image.clone(),
+                result_sender: result_tx,
+            })
+            .map_err(|_| {
+                OcrError::EngineError("OCR engine worker thread has terminated".to_string())
+            })?;
+
+        // 等待结果
+        result_rx.recv().map_err(|_| {
+            OcrError::EngineError("Failed to receive result from worker thread".to_string())
+        })?
+    }
+
+    /// 获取原图坐标下的文本概率图
+    ///
+    /// Get the text probability map in original image coordinates
+    pub fn get_probability_map(&self, image: &DynamicImage) -> OcrResult<ProbabilityMap> {
+        // 创建结果通道
+        let (result_tx, result_rx) = unbounded();
+
+        // 发送请求
+        self.request_sender
+            .send(OcrRequest::GetProbabilityMap {
+                image: image.clone(),
+                result_sender: result_tx,
+            })
+            .map_err(|_| {
+                OcrError::EngineError("OCR engine worker thread has terminated".to_string())
+            })?;
+
+        // 等待结果
+        result_rx.recv().map_err(|_| {
+            OcrError::EngineError("Failed to receive result from worker thread".to_string())
+        })?
+    }
+
+    /// 处理OCR并返回详细结果，包括页面旋转角度、文本框和置信度
+    ///
+    /// Process OCR and return detailed results, including the page rotation, boxes and confidences
+    pub fn process_ocr_detailed(&self, image: DynamicImage) -> OcrResult<PageResult> {
+        self.process_ocr_with_options(image, RecognizeOptions::default())
+    }
+
+    /// 按本次请求的识别选项（如字符过滤规则）处理OCR并返回详细结果
+    ///
+    /// Process OCR with recognition options for this request only (such as a character filter)
+    /// and return detailed results
+    pub fn process_ocr_with_options(
+        &self,
+        image: DynamicImage,
+        options: RecognizeOptions,
+    ) -> OcrResult<PageResult> {
+        // 创建结果通道
+        let (result_tx, result_rx) = unbounded();
+
+        // 发送请求
+        self.request_sender
+            .send(OcrRequest::ProcessOcrDetailed {
+                image,
+                options,
+                result_sender: result_tx,
+            })
+            .map_err(|_| {
+                OcrError::EngineError("OCR engine worker thread has terminated".to_string())
+            })?;
+
+        // 等待结果
+        result_rx.recv().map_err(|_| {
+            OcrError::EngineError("Failed to receive result from worker thread".to_string())
+        })?
     }
 
-    /// 使用字节数据的工作线程的主处理函数
+    /// 在工作线程中加载模型
     ///
-    /// Main processing function for the worker thread using byte data
-    fn run_worker_with_bytes(
+    /// Load the models on the worker thread
+    fn load_worker(
+        det_model_path: impl AsRef<Path>,
+        rec_model_path: impl AsRef<Path>,
+        keys_path: impl AsRef<Path>,
+        config: OcrEngineConfig,
+    ) -> OcrResult<Worker> {
+        // 初始化模型，应用自定义配置
+        let det = config.apply_to_det(Det::from_file(det_model_path)?);
+
+        let rec = config.apply_to_rec(Rec::from_file_with_options(
+            rec_model_path,
+            keys_path,
+            config.model_profile,
+            config.runtime,
+        )?);
+
+        Worker::new(det, rec, &config)
+    }
+
+    /// 在工作线程中从字节数据加载模型
+    ///
+    /// Load the models from byte data on the worker thread
+    fn load_worker_with_bytes(
         det_model_data: Vec<u8>,
         rec_model_data: Vec<u8>,
         keys_data: Vec<u8>,
-        receiver: Receiver<OcrRequest>,
-        rect_border_size: u32,
-        merge_boxes: bool,
-        merge_threshold: i32,
-    ) -> OcrResult<()> {
+        config: OcrEngineConfig,
+    ) -> OcrResult<Worker> {
         // 直接从字节数据初始化模型
-        let mut det = Det::from_bytes(&det_model_data)?
-            .with_rect_border_size(rect_border_size)
-            .with_merge_boxes(merge_boxes)
-            .with_merge_threshold(merge_threshold);
+        let det = config.apply_to_det(Det::from_bytes(&det_model_data)?);
+
+        let rec = config.apply_to_rec(Rec::from_bytes_with_keys_and_options(
+            &rec_model_data,
+            &keys_data,
+            config.model_profile,
+            config.runtime,
+        )?);
+
+        Worker::new(det, rec, &config)
+    }
+}
+
+/// 工作线程持有的模型
+///
+/// Models owned by the worker thread
+struct Worker {
+    det: Det,
+    rec: Rec,
+    cls: Option<Cls>,
+    doc_ori: Option<DocOri>,
+    auto_rotate: bool,
+}
+
+impl Worker {
+    /// 启发式页面方向估计时采样的文本框数量
+    /// Number of boxes sampled by the page orientation heuristic
+    const ORIENTATION_SAMPLES: usize = 5;
 
-        let mut rec = Rec::from_bytes_with_keys(&rec_model_data, &keys_data)?;
+    /// 按配置加载可选模型并创建工作线程状态
+    ///
+    /// Load the optional models from the configuration and create the worker state
+    fn new(det: Det, rec: Rec, config: &OcrEngineConfig) -> OcrResult<Self> {
+        Ok(Self {
+            det,
+            rec,
+            cls: config.load_cls()?,
+            doc_ori: config.load_doc_ori()?,
+            auto_rotate: config.auto_rotate,
+        })
+    }
 
+    /// 处理请求循环，直到收到关闭请求或通道关闭
+    ///
+    /// Request processing loop, runs until a shutdown request arrives or the channel closes
+    fn handle_requests(mut self, receiver: Receiver<OcrRequest>) {
         // 处理请求循环
         for request in receiver {
             match request {
@@ -504,7 +1128,7 @@ impl OcrEngine {
                     image,
                     result_sender,
                 } => {
-                    let result = det.find_text_img(&image);
+                    let result = self.det.find_text_img(&image);
                     // 发送结果，忽略接收端可能已关闭的错误
                     let _ = result_sender.send(result);
                 }
@@ -512,79 +1136,98 @@ impl OcrEngine {
                     image,
                     result_sender,
                 } => {
-                    let result = det.find_text_rect(&image);
+                    let result = self.det.find_text_rect(&image);
+                    let _ = result_sender.send(result);
+                }
+                OcrRequest::GetTextQuads {
+                    image,
+                    result_sender,
+                } => {
+                    let result = self.det.find_text_quads(&image);
                     let _ = result_sender.send(result);
                 }
                 OcrRequest::GetTextImages {
                     image,
                     result_sender,
                 } => {
-                    let result = det.find_text_img(&image);
+                    let result = self.det.find_text_img(&image);
                     let _ = result_sender.send(result);
                 }
                 OcrRequest::RecognizeText {
                     image,
                     result_sender,
                 } => {
-                    let result = rec.predict_str(&image);
+                    let result = self.recognize(image);
                     let _ = result_sender.send(result);
                 }
                 OcrRequest::ProcessOcr {
                     image,
                     result_sender,
                 } => {
-                    // 先检测文本区域
-                    match det.find_text_img(&image) {
-                        Ok(text_images) => {
-                            // 识别每个文本区域
-                            let mut results = Vec::with_capacity(text_images.len());
-                            for text_img in text_images {
-                                match rec.predict_str(&text_img) {
-                                    Ok(text) => results.push(text),
-                                    Err(e) => {
-                                        let _ = result_sender.send(Err(e));
-                                        break;
-                                    }
-                                }
-                            }
-                            let _ = result_sender.send(Ok(results));
-                        }
-                        Err(e) => {
-                            let _ = result_sender.send(Err(e));
-                        }
-                    }
+                    // 先检测文本区域，再识别每个文本区域
+                    let result = self.upright(image).and_then(|(image, _)| {
+                        let text_images = self.det.find_text_img(&image)?;
+                        self.recognize_all(text_images)
+                    });
+                    let _ = result_sender.send(result);
                 }
                 OcrRequest::GetTextImagesEfficient {
                     image,
                     result_sender,
                 } => {
-                    let result = det.find_text_img_efficient(&image);
+                    let result = self.det.find_text_img_efficient(&image);
                     let _ = result_sender.send(result);
                 }
                 OcrRequest::ProcessOcrEfficient {
                     image,
                     result_sender,
                 } => {
-                    // 使用高效裁剪先检测文本区域
-                    match det.find_text_img_efficient(&image) {
-                        Ok(text_images) => {
-                            // 识别每个文本区域
-                            let mut results = Vec::with_capacity(text_images.len());
-                            for text_img in text_images {
-                                match rec.predict_str(&text_img) {
-                                    Ok(text) => results.push(text),
-                                    Err(e) => {
-                                        let _ = result_sender.send(Err(e));
-                                        break;
-                                    }
-                                }
-                            }
-                            let _ = result_sender.send(Ok(results));
-                        }
-                        Err(e) => {
-                            let _ = result_sender.send(Err(e));
-                        }
-                    }
+                    // 使用高效裁剪先检测文本区域，再识别每个文本区域
+                    let result = self.upright(image).and_then(|(image, _)| {
+                        let text_images = self.det.find_text_img_efficient(&image)?;
+                        self.recognize_all(text_images)
+                    });
+                    let _ = result_sender.send(result);
+                }
+                OcrRequest::GetTextImagesRotated {
+                    image,
+                    result_sender,
+                } => {
+                    let result = self.det.find_text_img_rotated(&image);
+                    let _ = result_sender.send(result);
+                }
+                OcrRequest::ProcessOcrRotated {
+                    image,
+                    result_sender,
+                } => {
+                    // 使用透视校正后的文本行图像进行识别
+                    let result = self.upright(image).and_then(|(image, _)| {
+                        let text_images = self.det.find_text_img_rotated(&image)?;
+                        self.recognize_all(text_images)
+                    });
+                    let _ = result_sender.send(result);
+                }
+                OcrRequest::GetTextBoxes {
+                    image,
+                    result_sender,
+                } => {
+                    let result = self.det.find_text_boxes(&image);
+                    let _ = result_sender.send(result);
+                }
+                OcrRequest::GetProbabilityMap {
+                    image,
+                    result_sender,
+                } => {
+                    let result = self.det.probability_map(&image);
+                    let _ = result_sender.send(result);
+                }
+                OcrRequest::ProcessOcrDetailed {
+                    image,
+                    options,
+                    result_sender,
+                } => {
+                    let result = self.process_detailed(image, &options);
+                    let _ = result_sender.send(result);
                 }
                 OcrRequest::Shutdown => {
                     // 收到关闭请求，退出循环
@@ -592,8 +1235,214 @@ impl OcrEngine {
                 }
             }
         }
+    }
 
-        Ok(())
+    /// 完整OCR处理，返回页面旋转角度以及每行文本的位置和置信度
+    ///
+    /// Full OCR processing, returning the page rotation and the position and confidence of each line
+    fn process_detailed(
+        &mut self,
+        image: DynamicImage,
+        options: &RecognizeOptions,
+    ) -> OcrResult<PageResult> {
+        let (image, angle) = self.upright(image)?;
+        let boxes = self.det.find_text_boxes(&image)?;
+        if boxes.is_empty() {
+            return Ok(PageResult {
+                angle,
+                lines: Vec::new(),
+            });
+        }
+
+        let quads: Vec<TextQuad> = boxes.iter().map(|x| x.quad.clone()).collect();
+        let text_images = EfficientCropper::batch_perspective_crop(&ImageRef::from(image), &quads);
+
+        let results = self.recognize_spans(text_images, options)?;
+
+        let mut lines = Vec::with_capacity(boxes.len());
+        for (detected, line) in boxes.into_iter().zip(results) {
+            let (chars, words) =
+                result::line_spans(&line.spans, &detected.quad, self.rec.score_aggregation());
+            lines.push(TextLine {
+                text: line.text,
+                score: line.score,
+                det_score: detected.score,
+                rect: detected.rect,
+                vertical: detected.quad.is_vertical(),
+                quad: detected.quad,
+                chars,
+                words,
+                matches_pattern: line.matches_pattern,
+            });
+        }
+
+        Ok(PageResult { angle, lines })
+    }
+
+    /// 启用自动校正时将页面转回正向，返回转正后的图像和检测到的旋转角度
+    ///
+    /// When auto-rotation is enabled, turn the page upright and return the upright image
+    /// together with the detected rotation
+    fn upright(&mut self, image: DynamicImage) -> OcrResult<(DynamicImage, u32)> {
+        if !self.auto_rotate {
+            return Ok((image, 0));
+        }
+
+        match self.detect_page_angle(&image)? {
+            0 => Ok((image, 0)),
+            angle => Ok((rotate_upright(&image, angle), angle)),
+        }
+    }
+
+    /// 估计页面的顺时针旋转角度
+    ///
+    /// 加载了文档方向分类模型时直接使用模型；否则取主要方向上最大的几个文本框，分别按
+    /// 四个角度转正后识别，选择识别出的字符置信度总和最高的角度。
+    ///
+    /// 注意：启发式方法无法区分竖排中日文页面和横置的页面。开启竖排文本行旋转时，竖长文本框
+    /// 占多数的页面按竖排页面处理，不做旋转；横置的文档请使用方向分类模型。
+    ///
+    /// Estimate the clockwise rotation of the page
+    ///
+    /// Uses the document orientation classifier when loaded. Otherwise the largest boxes of the
+    /// dominant shape are recognized after turning them upright for each of the four angles,
+    /// and the angle with the highest total character confidence wins.
+    ///
+    /// Note: the heuristic cannot tell vertical CJK pages from sideways pages. While vertical
+    /// line rotation is enabled, pages dominated by tall boxes are taken as vertical pages and
+    /// left as they are; use the classifier model for sideways documents.
+    fn detect_page_angle(&mut self, image: &DynamicImage) -> OcrResult<u32> {
+        if let Some(doc_ori) = self.doc_ori.as_mut() {
+            return Ok(doc_ori.predict(image)?.angle);
+        }
+
+        let boxes = self.det.find_text_boxes(image)?;
+        let boxes = Self::orientation_samples(boxes, self.rec.rotate_vertical());
+        if boxes.is_empty() {
+            return Ok(0);
+        }
+
+        let samples: Vec<DynamicImage> = boxes
+            .iter()
+            .map(|x| {
+                image.crop_imm(
+                    x.rect.left() as u32,
+                    x.rect.top() as u32,
+                    x.rect.width(),
+                    x.rect.height(),
+                )
+            })
+            .collect();
+
+        let mut best = (0, f32::MIN);
+        for angle in [0, 90, 180, 270] {
+            let mut total = 0.0;
+            for sample in &samples {
+                // 转正后仍为竖长的样本会被识别模型当作竖排文本旋转，与其他角度无法区分，不参与计分
+                let sample = rotate_upright(sample, angle);
+                if geometry::is_vertical(sample.width() as f32, sample.height() as f32) {
+                    continue;
+                }
+                let (text, score) = self.rec.predict_with_confidence(&sample)?;
+                total += score * text.chars().count() as f32;
+            }
+            if total > best.1 {
+                best = (angle, total);
+            }
+        }
+
+        Ok(best.0)
+    }
+
+    /// 选择用于估计页面方向的文本框：主要方向上面积最大的几个框。竖长文本框占多数且开启了
+    /// 竖排文本行旋转时，页面按竖排页面处理，不返回任何框
+    fn orientation_samples(mut boxes: Vec<DetectedBox>, rotate_vertical: bool) -> Vec<DetectedBox> {
+        let area = |x: &DetectedBox| x.rect.width() as u64 * x.rect.height() as u64;
+        let is_tall = |x: &DetectedBox| x.rect.height() > x.rect.width();
+
+        // 文本框大多竖长时页面可能是横置的，也可能是竖排文本；后者在 0 度时已被识别器按竖排
+        // 文本行旋转，与 90 度无法区分
+        let tall_area: u64 = boxes.iter().filter(|x| is_tall(x)).map(area).sum();
+        let wide_area: u64 = boxes.iter().filter(|x| !is_tall(x)).map(area).sum();
+        let sideways = tall_area > wide_area;
+        if sideways && rotate_vertical {
+            return Vec::new();
+        }
+
+        boxes.retain(|x| is_tall(x) == sideways);
+        boxes.sort_by_key(|x| std::cmp::Reverse(area(x)));
+        boxes.truncate(Self::ORIENTATION_SAMPLES);
+        boxes
+    }
+
+    /// 识别单个文本区域图像，加载了方向分类模型时先校正上下颠倒的文本行
+    ///
+    /// Recognize a single text region image, first correcting upside-down lines when a
+    /// direction classifier is loaded
+    fn recognize(&mut self, text_img: DynamicImage) -> OcrResult<String> {
+        let text_img = match self.cls.as_mut() {
+            Some(cls) => cls.correct(text_img)?.0,
+            None => text_img,
+        };
+        self.rec.predict_str(&text_img)
+    }
+
+    /// 批量识别所有文本区域图像，返回文本和置信度，顺序与输入一致
+    ///
+    /// Recognize all text region images in batches, returning texts and confidences in input order
+    fn recognize_batch(&mut self, text_images: Vec<DynamicImage>) -> OcrResult<Vec<(String, f32)>> {
+        let results = self.recognize_spans(text_images, &RecognizeOptions::default())?;
+        Ok(results
+            .into_iter()
+            .map(|line| (line.text, line.score))
+            .collect())
+    }
+
+    /// 批量识别所有文本区域图像，返回每个字符及其在文本区域图像中的范围，顺序与输入一致；
+    /// 被方向分类器旋转过的图像，字符范围会映射回旋转前的方向
+    ///
+    /// Recognize all text region images in batches, returning every character with its extent in
+    /// the text region image, in input order; extents of images turned by the direction
+    /// classifier are mapped back to the original orientation
+    fn recognize_spans(
+        &mut self,
+        text_images: Vec<DynamicImage>,
+        options: &RecognizeOptions,
+    ) -> OcrResult<Vec<LineRecognition>> {
+        let mut flipped = vec![false; text_images.len()];
+        let text_images = match self.cls.as_mut() {
+            Some(cls) => text_images
+                .into_iter()
+                .zip(flipped.iter_mut())
+                .map(|(text_img, flipped)| {
+                    cls.correct(text_img).map(|(img, result)| {
+                        *flipped = cls.is_upside_down(&result);
+                        img
+                    })
+                })
+                .collect::<OcrResult<Vec<_>>>()?,
+            None => text_images,
+        };
+
+        let mut results = self.rec.predict_batch_with_options(&text_images, options)?;
+        for (line, _) in results
+            .iter_mut()
+            .zip(flipped)
+            .filter(|(_, flipped)| *flipped)
+        {
+            for span in line.spans.iter_mut() {
+                (span.start, span.end) = (1.0 - span.end, 1.0 - span.start);
+            }
+        }
+        Ok(results)
+    }
+
+    /// 批量识别所有文本区域图像
+    ///
+    /// Recognize all text region images in batches
+    fn recognize_all(&mut self, text_images: Vec<DynamicImage>) -> OcrResult<Vec<String>> {
+        let results = self.recognize_batch(text_images)?;
+        Ok(results.into_iter().map(|(text, _)| text).collect())
     }
 }
 
@@ -678,6 +1527,90 @@ impl OcrEngineManager {
         Ok(())
     }
 
+    /// 使用完整的引擎配置初始化全局OCR引擎
+    ///
+    /// Initialize the global OCR engine with a full engine configuration
+    pub fn initialize_with_options(
+        det_model_path: impl AsRef<Path>,
+        rec_model_path: impl AsRef<Path>,
+        keys_path: impl AsRef<Path>,
+        config: OcrEngineConfig,
+    ) -> OcrResult<()> {
+        let engine =
+            OcrEngine::new_with_options(det_model_path, rec_model_path, keys_path, config)?;
+
+        // 获取或初始化全局实例
+        let instance = INSTANCE.get_or_init(|| Arc::new(Mutex::new(None)));
+
+        // 更新引擎实例
+        let mut guard = instance.lock().map_err(|_| {
+            OcrError::EngineError("Failed to acquire lock on OCR engine manager".to_string())
+        })?;
+
+        *guard = Some(engine);
+
+        Ok(())
+    }
+
+    /// 从模型目录初始化全局OCR引擎
+    ///
+    /// Initialize the global OCR engine from a model directory
+    pub fn initialize_from_dir(dir: impl AsRef<Path>) -> OcrResult<()> {
+        Self::initialize_from_dir_with_options(dir, OcrEngineConfig::default())
+    }
+
+    /// 从模型目录和引擎配置初始化全局OCR引擎
+    ///
+    /// Initialize the global OCR engine from a model directory and an engine configuration
+    pub fn initialize_from_dir_with_options(
+        dir: impl AsRef<Path>,
+        config: OcrEngineConfig,
+    ) -> OcrResult<()> {
+        let engine = OcrEngine::from_dir_with_options(dir, config)?;
+
+        // 获取或初始化全局实例
+        let instance = INSTANCE.get_or_init(|| Arc::new(Mutex::new(None)));
+
+        // 更新引擎实例
+        let mut guard = instance.lock().map_err(|_| {
+            OcrError::EngineError("Failed to acquire lock on OCR engine manager".to_string())
+        })?;
+
+        *guard = Some(engine);
+
+        Ok(())
+    }
+
+    /// 从单文件模型包初始化全局OCR引擎
+    ///
+    /// Initialize the global OCR engine from a single-file model bundle
+    pub fn initialize_from_bundle(bundle_path: impl AsRef<Path>) -> OcrResult<()> {
+        Self::initialize_from_bundle_with_options(bundle_path, OcrEngineConfig::default())
+    }
+
+    /// 从单文件模型包和引擎配置初始化全局OCR引擎
+    ///
+    /// Initialize the global OCR engine from a single-file model bundle and an engine
+    /// configuration
+    pub fn initialize_from_bundle_with_options(
+        bundle_path: impl AsRef<Path>,
+        config: OcrEngineConfig,
+    ) -> OcrResult<()> {
+        let engine = OcrEngine::from_bundle_with_options(bundle_path, config)?;
+
+        // 获取或初始化全局实例
+        let instance = INSTANCE.get_or_init(|| Arc::new(Mutex::new(None)));
+
+        // 更新引擎实例
+        let mut guard = instance.lock().map_err(|_| {
+            OcrError::EngineError("Failed to acquire lock on OCR engine manager".to_string())
+        })?;
+
+        *guard = Some(engine);
+
+        Ok(())
+    }
+
     /// 使用自定义配置和字节数据初始化全局OCR引擎
     ///
     /// Initialize the global OCR engine with custom configuration and byte data
@@ -711,6 +1644,35 @@ impl OcrEngineManager {
         Ok(())
     }
 
+    /// 使用完整的引擎配置和字节数据初始化全局OCR引擎
+    ///
+    /// Initialize the global OCR engine with a full engine configuration and byte data
+    pub fn initialize_with_options_and_bytes(
+        det_model_data: &[u8],
+        rec_model_data: &[u8],
+        keys_data: &[u8],
+        config: OcrEngineConfig,
+    ) -> OcrResult<()> {
+        let engine = OcrEngine::new_with_options_and_bytes(
+            det_model_data,
+            rec_model_data,
+            keys_data,
+            config,
+        )?;
+
+        // 获取或初始化全局实例
+        let instance = INSTANCE.get_or_init(|| Arc::new(Mutex::new(None)));
+
+        // 更新引擎实例
+        let mut guard = instance.lock().map_err(|_| {
+            OcrError::EngineError("Failed to acquire lock on OCR engine manager".to_string())
+        })?;
+
+        *guard = Some(engine);
+
+        Ok(())
+    }
+
     /// 获取全局OCR引擎实例
     ///
     /// Get the global OCR engine instance
@@ -753,6 +1715,22 @@ impl OcrEngineManager {
         engine.get_text_rects(image)
     }
 
+    /// 获取文本区域的旋转四边形框
+    ///
+    /// Get text region rotated quadrilaterals
+    pub fn get_text_quads(image: &DynamicImage) -> OcrResult<Vec<TextQuad>> {
+        let instance = Self::get_instance()?;
+        let guard = instance.lock().map_err(|_| {
+            OcrError::EngineError("Failed to acquire lock on OCR engine manager".to_string())
+        })?;
+
+        let engine = guard
+            .as_ref()
+            .ok_or_else(|| OcrError::EngineError("OCR engine not initialized".to_string()))?;
+
+        engine.get_text_quads(image)
+    }
+
     /// 获取文本区域图像
     ///
     /// Get text region images
@@ -832,4 +1810,139 @@ impl OcrEngineManager {
 
         engine.process_ocr_efficient(image)
     }
+
+    /// 使用旋转四边形框和透视校正获取文本区域图像
+    ///
+    /// Get text region images using rotated quads and perspective correction
+    pub fn get_text_images_rotated(image: &DynamicImage) -> OcrResult<Vec<DynamicImage>> {
+        let instance = Self::get_instance()?;
+        let guard = instance.lock().map_err(|_| {
+            OcrError::EngineError("Failed to acquire lock on OCR engine manager".to_string())
+        })?;
+
+        let engine = guard
+            .as_ref()
+            .ok_or_else(|| OcrError::EngineError("OCR engine not initialized".to_string()))?;
+
+        engine.get_text_images_rotated(image)
+    }
+
+    /// 使用旋转四边形框和透视校正的完整OCR处理
+    ///
+    /// Complete OCR processing using rotated quads and perspective correction
+    pub fn process_ocr_rotated(image: DynamicImage) -> OcrResult<Vec<String>> {
+        let instance = Self::get_instance()?;
+        let guard = instance.lock().map_err(|_| {
+            OcrError::EngineError("Failed to acquire lock on OCR engine manager".to_string())
+        })?;
+
+        let engine = guard
+            .as_ref()
+            .ok_or_else(|| OcrError::EngineError("OCR engine not initialized".to_string()))?;
+
+        engine.process_ocr_rotated(image)
+    }
+
+    /// 获取带检测得分的文本框
+    ///
+    /// Get text boxes with their detection scores
+    pub fn get_text_boxes(image: &DynamicImage) -> OcrResult<Vec<DetectedBox>> {
+        let instance = Self::get_instance()?;
+        let guard = instance.lock().map_err(|_| {
+            OcrError::EngineError("Failed to acquire lock on OCR engine manager".to_string())
+        })?;
+
+        let engine = guard
+            .as_ref()
+            .ok_or_else(|| OcrError::EngineError("OCR engine not initialized".to_string()))?;
+
+        engine.get_text_boxes(image)
+    }
+
+    /// 获取原图坐标下的文本概率图
+    ///
+    /// Get the text probability map in original image coordinates
+    pub fn get_probability_map(image: &DynamicImage) -> OcrResult<ProbabilityMap> {
+        let instance = Self::get_instance()?;
+        let guard = instance.lock().map_err(|_| {
+            OcrError::EngineError("Failed to acquire lock on OCR engine manager".to_string())
+        })?;
+
+        let engine = guard
+            .as_ref()
+            .ok_or_else(|| OcrError::EngineError("OCR engine not initialized".to_string()))?;
+
+        engine.get_probability_map(image)
+    }
+
+    /// 处理OCR并返回详细结果，包括页面旋转角度、文本框和置信度
+    ///
+    /// Process OCR and return detailed results, including the page rotation, boxes and confidences
+    pub fn process_ocr_detailed(image: DynamicImage) -> OcrResult<PageResult> {
+        let instance = Self::get_instance()?;
+        let guard = instance.lock().map_err(|_| {
+            OcrError::EngineError("Failed to acquire lock on OCR engine manager".to_string())
+        })?;
+
+        let engine = guard
+            .as_ref()
+            .ok_or_else(|| OcrError::EngineError("OCR engine not initialized".to_string()))?;
+
+        engine.process_ocr_detailed(image)
+    }
+
+    /// 按本次请求的识别选项处理OCR并返回详细结果
+    ///
+    /// Process OCR with recognition options for this request only and return detailed results
+    pub fn process_ocr_with_options(
+        image: DynamicImage,
+        options: RecognizeOptions,
+    ) -> OcrResult<PageResult> {
+        let instance = Self::get_instance()?;
+        let guard = instance.lock().map_err(|_| {
+            OcrError::EngineError("Failed to acquire lock on OCR engine manager".to_string())
+        })?;
+
+        let engine = guard
+            .as_ref()
+            .ok_or_else(|| OcrError::EngineError("OCR engine not initialized".to_string()))?;
+
+        engine.process_ocr_with_options(image, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detected(left: i32, top: i32, width: u32, height: u32) -> DetectedBox {
+        let rect = Rect::at(left, top).of_size(width, height);
+        DetectedBox {
+            rect,
+            quad: TextQuad::from_rect(&rect),
+            score: 0.9,
+        }
+    }
+
+    #[test]
+    fn test_orientation_samples() {
+        // 横排页面取面积最大的横长文本框
+        let page = vec![
+            detected(0, 0, 200, 30),
+            detected(0, 50, 400, 30),
+            detected(0, 100, 20, 60),
+        ];
+        let samples = Worker::orientation_samples(page, true);
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].rect.width(), 400);
+
+        // 竖长文本框占多数：开启竖排旋转时视为竖排页面，不做估计
+        let page = vec![
+            detected(0, 0, 30, 300),
+            detected(50, 0, 30, 400),
+            detected(100, 0, 200, 20),
+        ];
+        assert!(Worker::orientation_samples(page.clone(), true).is_empty());
+        assert_eq!(Worker::orientation_samples(page, false).len(), 2);
+    }
 }
@@ -61,6 +61,11 @@ pub enum OcrError {
     #[error("Thread error: {0}")]
     ThreadError(String),
 
+    /// 模型包错误，如格式不正确或校验失败
+    /// Model bundle errors, such as an invalid format or a checksum mismatch
+    #[error("Model bundle error: {0}")]
+    BundleError(String),
+
     #[cfg(feature="fast_resize")]
     /// 图像缩放错误
     /// Image resize errors
@@ -0,0 +1,513 @@
+use imageproc::{point::Point, rect::Rect};
+
+/// 竖排文本的高宽比阈值，高宽比不小于该值的文本行视为竖排
+///
+/// Height/width ratio at which a text line is treated as vertical
+pub const VERTICAL_ASPECT_RATIO: f32 = 1.5;
+
+/// 文本四边形框，通常是文本区域的最小外接旋转矩形
+///
+/// Text quadrilateral, usually the minimum-area rotated rectangle of a text region
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextQuad {
+    /// 四个角点，顺序为左上、右上、右下、左下
+    /// Corner points in order: top-left, top-right, bottom-right, bottom-left
+    pub points: [Point<f32>; 4],
+    /// 可选的文本区域轮廓多边形
+    /// Optional polygon outlining the text region
+    pub polygon: Option<Vec<Point<f32>>>,
+}
+
+impl TextQuad {
+    /// 由四个角点创建四边形，角点会被重新排序为左上、右上、右下、左下
+    ///
+    /// Create a quad from four corner points, reordered to top-left, top-right, bottom-right, bottom-left
+    pub fn new(points: [Point<f32>; 4]) -> Self {
+        Self {
+            points: order_points(points),
+            polygon: None,
+        }
+    }
+
+    /// 计算点集的最小外接旋转矩形
+    ///
+    /// Compute the minimum-area rotated rectangle enclosing a set of points
+    pub fn from_points(points: &[Point<u32>]) -> Option<Self> {
+        min_area_rect(points).map(Self::new)
+    }
+
+    /// 由轴对齐矩形创建四边形
+    ///
+    /// Create a quad from an axis-aligned rectangle
+    pub fn from_rect(rect: &Rect) -> Self {
+        let left = rect.left() as f32;
+        let top = rect.top() as f32;
+        let right = (rect.right() + 1) as f32;
+        let bottom = (rect.bottom() + 1) as f32;
+        Self {
+            points: [
+                Point::new(left, top),
+                Point::new(right, top),
+                Point::new(right, bottom),
+                Point::new(left, bottom),
+            ],
+            polygon: None,
+        }
+    }
+
+    /// 四边形宽度（上下两边的较大值）
+    ///
+    /// Quad width (the longer of the top and bottom edges)
+    pub fn width(&self) -> f32 {
+        let [tl, tr, br, bl] = self.points;
+        distance(tl, tr).max(distance(bl, br))
+    }
+
+    /// 四边形高度（左右两边的较大值）
+    ///
+    /// Quad height (the longer of the left and right edges)
+    pub fn height(&self) -> f32 {
+        let [tl, tr, br, bl] = self.points;
+        distance(tl, bl).max(distance(tr, br))
+    }
+
+    /// 是否为竖排文本行（高宽比不小于 [`VERTICAL_ASPECT_RATIO`]）
+    ///
+    /// Whether this is a vertical text line (height/width ratio of at least [`VERTICAL_ASPECT_RATIO`])
+    pub fn is_vertical(&self) -> bool {
+        is_vertical(self.width(), self.height())
+    }
+
+    /// 上边相对水平方向的倾斜角度（度）
+    ///
+    /// Inclination of the top edge relative to horizontal, in degrees
+    pub fn angle(&self) -> f32 {
+        let [tl, tr, _, _] = self.points;
+        (tr.y - tl.y).atan2(tr.x - tl.x).to_degrees()
+    }
+
+    /// 四边形中心点
+    ///
+    /// Center point of the quad
+    pub fn center(&self) -> Point<f32> {
+        let (sx, sy) = self
+            .points
+            .iter()
+            .fold((0.0, 0.0), |(sx, sy), p| (sx + p.x, sy + p.y));
+        Point::new(sx / 4.0, sy / 4.0)
+    }
+
+    /// 计算包含四边形的轴对齐矩形，并裁剪到图像范围内
+    ///
+    /// Compute the axis-aligned rectangle enclosing the quad, clipped to the image bounds
+    pub fn bounding_rect(&self, width: u32, height: u32) -> Rect {
+        let (mut x_min, mut y_min) = (f32::MAX, f32::MAX);
+        let (mut x_max, mut y_max) = (f32::MIN, f32::MIN);
+        for p in &self.points {
+            x_min = x_min.min(p.x);
+            y_min = y_min.min(p.y);
+            x_max = x_max.max(p.x);
+            y_max = y_max.max(p.y);
+        }
+
+        let left = (x_min.floor() as i32).clamp(0, width as i32 - 1);
+        let top = (y_min.floor() as i32).clamp(0, height as i32 - 1);
+        let right = (x_max.ceil() as i32).clamp(left + 1, width as i32);
+        let bottom = (y_max.ceil() as i32).clamp(top + 1, height as i32);
+
+        Rect::at(left, top).of_size((right - left) as u32, (bottom - top) as u32)
+    }
+
+    /// 沿四边形自身的坐标轴向外扩展指定距离
+    ///
+    /// Grow the quad outwards along its own axes by the given distance
+    pub fn expand(&self, distance: f32) -> Self {
+        let [tl, tr, _, bl] = self.points;
+        let center = self.center();
+        let (ux, uy) = unit(tl, tr);
+        let (vx, vy) = unit(tl, bl);
+        let half_w = self.width() / 2.0 + distance;
+        let half_h = self.height() / 2.0 + distance;
+
+        let corner = |sw: f32, sh: f32| {
+            Point::new(
+                center.x + sw * half_w * ux + sh * half_h * vx,
+                center.y + sw * half_w * uy + sh * half_h * vy,
+            )
+        };
+
+        Self {
+            points: [
+                corner(-1.0, -1.0),
+                corner(1.0, -1.0),
+                corner(1.0, 1.0),
+                corner(-1.0, 1.0),
+            ],
+            polygon: self
+                .polygon
+                .as_ref()
+                .map(|polygon| offset_polygon(polygon, distance)),
+        }
+    }
+
+    /// 按比例缩放所有点的坐标
+    ///
+    /// Scale the coordinates of all points
+    pub fn scale(mut self, scale_x: f32, scale_y: f32) -> Self {
+        let scale_point = |p: &mut Point<f32>| {
+            p.x *= scale_x;
+            p.y *= scale_y;
+        };
+        self.points.iter_mut().for_each(scale_point);
+        if let Some(polygon) = self.polygon.as_mut() {
+            polygon.iter_mut().for_each(scale_point);
+        }
+        self
+    }
+
+    /// 按给定偏移平移所有点的坐标
+    ///
+    /// Translate all points by the given offset
+    pub fn translate(mut self, dx: f32, dy: f32) -> Self {
+        let translate_point = |p: &mut Point<f32>| {
+            p.x += dx;
+            p.y += dy;
+        };
+        self.points.iter_mut().for_each(translate_point);
+        if let Some(polygon) = self.polygon.as_mut() {
+            polygon.iter_mut().for_each(translate_point);
+        }
+        self
+    }
+
+    /// 截取沿阅读方向位于 `start`~`end`（0~1，占文本行长度的比例）之间的部分；
+    /// 竖排文本行的阅读方向为从上到下
+    ///
+    /// Take the part between `start` and `end` (0~1, fractions of the line length) along the
+    /// reading direction; vertical lines read top to bottom
+    pub fn span(&self, start: f32, end: f32) -> Self {
+        let [tl, tr, br, bl] = self.points;
+        let lerp = |a: Point<f32>, b: Point<f32>, t: f32| {
+            Point::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+        };
+        let (start, end) = (start.clamp(0.0, 1.0), end.clamp(0.0, 1.0));
+
+        let points = if self.is_vertical() {
+            [
+                lerp(tl, bl, start),
+                lerp(tr, br, start),
+                lerp(tr, br, end),
+                lerp(tl, bl, end),
+            ]
+        } else {
+            [
+                lerp(tl, tr, start),
+                lerp(tl, tr, end),
+                lerp(bl, br, end),
+                lerp(bl, br, start),
+            ]
+        };
+
+        Self {
+            points,
+            polygon: None,
+        }
+    }
+
+    /// 合并两个四边形，返回包含两者全部角点的最小外接旋转矩形
+    ///
+    /// Merge two quads into the minimum-area rotated rectangle enclosing all of their corners
+    pub fn union(&self, other: &TextQuad) -> Self {
+        let points: Vec<(f32, f32)> = self
+            .points
+            .iter()
+            .chain(other.points.iter())
+            .map(|p| (p.x, p.y))
+            .collect();
+        let hull = convex_hull_f32(points);
+
+        match min_area_rect_of_hull(&hull) {
+            Some(corners) => Self::new(corners),
+            None => self.clone(),
+        }
+    }
+
+    /// 判断点是否位于四边形内部（含边界）
+    ///
+    /// Check whether a point lies inside the quad (boundary included)
+    pub fn contains(&self, point: Point<f32>) -> bool {
+        let mut sign = 0.0f32;
+        for i in 0..4 {
+            let a = self.points[i];
+            let b = self.points[(i + 1) % 4];
+            let cross = (b.x - a.x) * (point.y - a.y) - (b.y - a.y) * (point.x - a.x);
+            if cross.abs() <= 1e-3 {
+                continue;
+            }
+            if sign == 0.0 {
+                sign = cross.signum();
+            } else if cross.signum() != sign {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// 将所有点限制在图像范围内
+    ///
+    /// Clamp all points into the image bounds
+    pub fn clamp(mut self, width: u32, height: u32) -> Self {
+        let max_x = width.saturating_sub(1) as f32;
+        let max_y = height.saturating_sub(1) as f32;
+        let clamp_point = |p: &mut Point<f32>| {
+            p.x = p.x.clamp(0.0, max_x);
+            p.y = p.y.clamp(0.0, max_y);
+        };
+        self.points.iter_mut().for_each(clamp_point);
+        if let Some(polygon) = self.polygon.as_mut() {
+            polygon.iter_mut().for_each(clamp_point);
+        }
+        self
+    }
+}
+
+/// 按高宽比判断给定尺寸的文本行是否为竖排
+///
+/// Check by aspect ratio whether a text line of the given size is vertical
+pub fn is_vertical(width: f32, height: f32) -> bool {
+    width > 0.0 && height / width >= VERTICAL_ASPECT_RATIO
+}
+
+/// 计算点集的最小外接旋转矩形，返回左上、右上、右下、左下四个角点
+///
+/// Compute the minimum-area rotated rectangle of a point set, returning the
+/// top-left, top-right, bottom-right and bottom-left corners
+pub fn min_area_rect(points: &[Point<u32>]) -> Option<[Point<f32>; 4]> {
+    let points: Vec<Point<i32>> = points
+        .iter()
+        .map(|p| Point::new(p.x as i32, p.y as i32))
+        .collect();
+    let hull = imageproc::geometry::convex_hull(points);
+    let hull: Vec<(f32, f32)> = hull.iter().map(|p| (p.x as f32, p.y as f32)).collect();
+    min_area_rect_of_hull(&hull)
+}
+
+/// 对凸包使用旋转卡壳求最小外接矩形
+///
+/// Find the minimum-area rectangle of a convex hull with rotating calipers
+fn min_area_rect_of_hull(hull: &[(f32, f32)]) -> Option<[Point<f32>; 4]> {
+    if hull.len() < 3 {
+        return None;
+    }
+
+    let mut best: Option<(f32, [Point<f32>; 4])> = None;
+
+    // 旋转卡壳：最小外接矩形必有一条边与凸包的某条边重合
+    for i in 0..hull.len() {
+        let (x0, y0) = hull[i];
+        let (x1, y1) = hull[(i + 1) % hull.len()];
+        let len = ((x1 - x0).powi(2) + (y1 - y0).powi(2)).sqrt();
+        if len == 0.0 {
+            continue;
+        }
+        let (ux, uy) = ((x1 - x0) / len, (y1 - y0) / len);
+
+        let (mut min_u, mut max_u) = (f32::MAX, f32::MIN);
+        let (mut min_v, mut max_v) = (f32::MAX, f32::MIN);
+        for &(x, y) in hull {
+            let u = x * ux + y * uy;
+            let v = -x * uy + y * ux;
+            min_u = min_u.min(u);
+            max_u = max_u.max(u);
+            min_v = min_v.min(v);
+            max_v = max_v.max(v);
+        }
+
+        let area = (max_u - min_u) * (max_v - min_v);
+        if best.as_ref().is_none_or(|(best_area, _)| area < *best_area) {
+            let corner = |u: f32, v: f32| Point::new(u * ux - v * uy, u * uy + v * ux);
+            best = Some((
+                area,
+                [
+                    corner(min_u, min_v),
+                    corner(max_u, min_v),
+                    corner(max_u, max_v),
+                    corner(min_u, max_v),
+                ],
+            ));
+        }
+    }
+
+    best.map(|(_, corners)| order_points(corners))
+}
+
+/// 将四个角点排序为左上、右上、右下、左下（与 PaddleOCR 的 get_mini_boxes 一致）
+///
+/// Order four corners as top-left, top-right, bottom-right, bottom-left (same as PaddleOCR's get_mini_boxes)
+pub fn order_points(mut points: [Point<f32>; 4]) -> [Point<f32>; 4] {
+    points.sort_by(|a, b| a.x.total_cmp(&b.x));
+
+    let (tl, bl) = if points[1].y > points[0].y {
+        (points[0], points[1])
+    } else {
+        (points[1], points[0])
+    };
+    let (tr, br) = if points[3].y > points[2].y {
+        (points[2], points[3])
+    } else {
+        (points[3], points[2])
+    };
+
+    [tl, tr, br, bl]
+}
+
+/// 将多边形的每条边沿法线方向向外平移指定距离
+///
+/// Offset every edge of a polygon outwards along its normal by the given distance
+pub fn offset_polygon(polygon: &[Point<f32>], distance: f32) -> Vec<Point<f32>> {
+    let n = polygon.len();
+    if n < 3 || distance == 0.0 {
+        return polygon.to_vec();
+    }
+
+    // 根据有向面积判断顶点顺序，保证法线朝外
+    let signed_area: f32 = (0..n)
+        .map(|i| {
+            let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum();
+    let orientation = if signed_area >= 0.0 { 1.0 } else { -1.0 };
+
+    let normal = |a: Point<f32>, b: Point<f32>| {
+        let (ux, uy) = unit(a, b);
+        (uy * orientation, -ux * orientation)
+    };
+
+    (0..n)
+        .map(|i| {
+            let prev = polygon[(i + n - 1) % n];
+            let curr = polygon[i];
+            let next = polygon[(i + 1) % n];
+            let (n1x, n1y) = normal(prev, curr);
+            let (n2x, n2y) = normal(curr, next);
+
+            // 斜接方向，限制尖角处的伸长量
+            let (mx, my) = (n1x + n2x, n1y + n2y);
+            let dot = (mx * n1x + my * n1y).max(0.5);
+            Point::new(curr.x + mx * distance / dot, curr.y + my * distance / dot)
+        })
+        .collect()
+}
+
+/// 计算浮点点集的凸包（单调链算法），按逆时针顺序返回
+///
+/// Compute the convex hull of floating point coordinates (monotone chain), in counter-clockwise order
+fn convex_hull_f32(mut points: Vec<(f32, f32)>) -> Vec<(f32, f32)> {
+    points.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    let cross = |o: (f32, f32), a: (f32, f32), b: (f32, f32)| {
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    };
+
+    let build_chain = |points: &mut dyn Iterator<Item = (f32, f32)>| {
+        let mut chain: Vec<(f32, f32)> = Vec::new();
+        for p in points {
+            while chain.len() >= 2
+                && cross(chain[chain.len() - 2], chain[chain.len() - 1], p) <= 0.0
+            {
+                chain.pop();
+            }
+            chain.push(p);
+        }
+        // 每条链的末点是另一条链的起点
+        chain.pop();
+        chain
+    };
+
+    let mut hull = build_chain(&mut points.iter().copied());
+    hull.extend(build_chain(&mut points.iter().rev().copied()));
+    hull
+}
+
+#[inline]
+fn distance(a: Point<f32>, b: Point<f32>) -> f32 {
+    ((b.x - a.x).powi(2) + (b.y - a.y).powi(2)).sqrt()
+}
+
+#[inline]
+fn unit(from: Point<f32>, to: Point<f32>) -> (f32, f32) {
+    let len = distance(from, to);
+    if len == 0.0 {
+        (0.0, 0.0)
+    } else {
+        ((to.x - from.x) / len, (to.y - from.y) / len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_area_rect_rotated() {
+        // 倾斜约26.57度的矩形的四个角点及一个内部点
+        let points = vec![
+            Point::new(4u32, 0),
+            Point::new(44, 20),
+            Point::new(40, 28),
+            Point::new(0, 8),
+            Point::new(22, 14),
+        ];
+        let quad = TextQuad::from_points(&points).unwrap();
+        assert!((quad.width() - 2000f32.sqrt()).abs() < 1e-3);
+        assert!((quad.height() - 80f32.sqrt()).abs() < 1e-3);
+        assert!((quad.angle() - 0.5f32.atan().to_degrees()).abs() < 1e-3);
+        assert!((quad.points[0].x - 4.0).abs() < 1e-3 && quad.points[0].y.abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_expand_and_bounding_rect() {
+        let quad = TextQuad::from_rect(&Rect::at(10, 10).of_size(20, 10));
+        let expanded = quad.expand(2.0);
+        assert!((expanded.width() - 24.0).abs() < 1e-3);
+        assert!((expanded.height() - 14.0).abs() < 1e-3);
+
+        assert!(expanded.contains(Point::new(9.0, 9.0)));
+        assert!(!quad.contains(Point::new(9.0, 9.0)));
+
+        let rect = expanded.bounding_rect(100, 100);
+        assert_eq!((rect.left(), rect.top()), (8, 8));
+        assert_eq!((rect.width(), rect.height()), (24, 14));
+    }
+
+    #[test]
+    fn test_span() {
+        let quad = TextQuad::from_rect(&Rect::at(0, 0).of_size(100, 20));
+        let span = quad.span(0.25, 0.5);
+        assert_eq!(span.points[0], Point::new(25.0, 0.0));
+        assert_eq!(span.points[2], Point::new(50.0, 20.0));
+
+        // 竖排文本行沿从上到下的方向截取
+        let quad = TextQuad::from_rect(&Rect::at(0, 0).of_size(20, 100));
+        let span = quad.span(0.5, 1.0);
+        assert_eq!(span.points[0], Point::new(0.0, 50.0));
+        assert_eq!(span.points[2], Point::new(20.0, 100.0));
+    }
+
+    #[test]
+    fn test_union() {
+        let a = TextQuad::from_rect(&Rect::at(0, 10).of_size(60, 20));
+        let b = TextQuad::from_rect(&Rect::at(40, 12).of_size(50, 18));
+        let merged = a.union(&b);
+
+        let rect = merged.bounding_rect(200, 200);
+        assert_eq!((rect.left(), rect.top()), (0, 10));
+        assert_eq!((rect.width(), rect.height()), (90, 20));
+        assert!(merged.angle().abs() < 1e-3);
+    }
+}
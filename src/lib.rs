@@ -6,7 +6,10 @@
 //! ## 主要功能 (Main Features)
 //!
 //! - 文本检测 (Text Detection): 定位图像中的文本区域
+//! - 方向分类 (Direction Classification): 可选，校正上下颠倒的文本行
+//! - 页面方向校正 (Page Orientation): 可选，自动转正旋转了 90°/180°/270° 的页面
 //! - 文本识别 (Text Recognition): 识别检测到的文本内容
+//! - 束搜索解码 (Beam Search Decoding): 可选，结合词典或字符级语言模型纠正易混字符
 //! - 线程安全的OCR引擎 (Thread-safe OCR Engine): 多线程环境下安全使用OCR功能
 //!
 //! ## 使用示例 (Usage Example)
@@ -73,10 +76,23 @@
 //! }
 //! ```
 
+pub mod bundle;
+pub mod charset;
+pub mod cls;
+pub mod ctc;
 pub mod det;
 pub mod engine;
 pub mod error;
+pub mod geometry;
+pub mod lm;
+pub mod model_dir;
+pub mod model_info;
+pub mod orientation;
+pub mod pattern;
+pub mod profile;
 pub mod rec;
+pub mod result;
+pub mod runtime;
 
 pub mod efficient_cropping;
 
@@ -84,10 +100,25 @@ pub mod efficient_cropping;
 // C API module
 pub mod capi;
 
-pub use det::Det;
-pub use engine::{OcrEngine, OcrEngineManager};
+pub use bundle::ModelBundle;
+pub use charset::{CharFilter, CharSet};
+pub use cls::{Cls, ClsResult};
+pub use ctc::{CtcDecoder, CtcMode, CtcToken};
+pub use det::{Det, DetOptions, DetectedBox, LimitType, ProbabilityMap, TileOptions};
+pub use engine::{ModelSource, OcrEngine, OcrEngineConfig, OcrEngineManager};
 pub use error::{OcrError, OcrResult};
-pub use rec::Rec;
+pub use geometry::TextQuad;
+pub use lm::{CharNgramLm, LanguageModel, Lexicon};
+pub use model_dir::ModelDir;
+pub use model_info::{ModelInfo, TensorDataType, TensorInfo};
+pub use orientation::DocOri;
+pub use pattern::{Pattern, PatternState};
+pub use profile::{ModelProfile, Normalization, OutputLayout};
+pub use rec::{Rec, RecognizeOptions};
+pub use result::{
+    CharSpan, LineRecognition, PageResult, RecognizedChar, ScoreAggregation, TextLine, TextSpan,
+};
+pub use runtime::{ForwardType, MemoryMode, PowerMode, Precision, RuntimeOptions};
 
 // 导出优化组件 (Export optimization components) - 将取代原engine
 pub use efficient_cropping::{EfficientCropper, ImageRef};
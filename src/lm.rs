@@ -0,0 +1,450 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::path::Path;
+
+use crate::error::{OcrError, OcrResult};
+
+/// 语言模型，在束搜索解码中为候选文本打分
+///
+/// Language model used to score candidate text during beam search decoding
+pub trait LanguageModel: Send + Sync + Debug {
+    /// 在已解码文本 `context` 之后出现字符 `ch` 的对数概率（自然对数）
+    ///
+    /// Natural-log probability of `ch` following the decoded text `context`
+    fn log_prob(&self, context: &[char], ch: char) -> f32;
+
+    /// 文本在 `context` 处结束的对数概率（自然对数），默认不做调整
+    ///
+    /// Natural-log probability of the text ending after `context`; no adjustment by default
+    fn end_log_prob(&self, _context: &[char]) -> f32 {
+        0.0
+    }
+}
+
+/// 词典约束：以空白分隔的词不在词典中时施加惩罚
+///
+/// Word lexicon: whitespace-separated words that are not in the lexicon are penalized
+#[derive(Debug, Clone)]
+pub struct Lexicon {
+    words: HashSet<String>,
+    prefixes: HashSet<String>,
+    oov_penalty: f32,
+}
+
+impl Lexicon {
+    /// 默认的词典外惩罚（自然对数）
+    /// Default out-of-vocabulary penalty (natural log)
+    pub const OOV_PENALTY_DEFAULT: f32 = -5.0;
+
+    /// 由词列表创建词典
+    ///
+    /// Create a lexicon from a list of words
+    pub fn new<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let words: HashSet<String> = words
+            .into_iter()
+            .map(Into::into)
+            .filter(|word| !word.is_empty())
+            .collect();
+
+        let mut prefixes = HashSet::new();
+        for word in &words {
+            for (end, _) in word.char_indices().skip(1) {
+                prefixes.insert(word[..end].to_string());
+            }
+            prefixes.insert(word.clone());
+        }
+
+        Self {
+            words,
+            prefixes,
+            oov_penalty: Self::OOV_PENALTY_DEFAULT,
+        }
+    }
+
+    /// 从文本内容创建词典，每行一个词
+    ///
+    /// Create a lexicon from text content with one word per line
+    pub fn from_lines(content: &str) -> Self {
+        Self::new(content.lines().map(str::trim))
+    }
+
+    /// 从文本文件创建词典，每行一个词
+    ///
+    /// Create a lexicon from a text file with one word per line
+    pub fn from_file(path: impl AsRef<Path>) -> OcrResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(Self::from_lines(&content))
+    }
+
+    /// 设置词典外惩罚（自然对数，应不大于 0）
+    ///
+    /// Set the out-of-vocabulary penalty (natural log, should not be positive)
+    pub fn with_oov_penalty(mut self, oov_penalty: f32) -> Self {
+        self.oov_penalty = oov_penalty;
+        self
+    }
+
+    /// 词典中的词数
+    ///
+    /// Number of words in the lexicon
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// 词典是否为空
+    ///
+    /// Whether the lexicon is empty
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// 是否包含给定的词
+    ///
+    /// Whether the lexicon contains the given word
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(word)
+    }
+
+    /// 获取 `context` 末尾尚未结束的词
+    fn current_word(context: &[char]) -> String {
+        let start = context
+            .iter()
+            .rposition(|ch| ch.is_whitespace())
+            .map_or(0, |pos| pos + 1);
+        context[start..].iter().collect()
+    }
+
+    /// 词结束时的得分：完整的词不惩罚；是词典中某个词的前缀但不完整时惩罚；
+    /// 已经偏离词典的词在偏离时已惩罚过，不再重复
+    fn word_end_score(&self, word: &str) -> f32 {
+        if word.is_empty() || self.words.contains(word) || !self.prefixes.contains(word) {
+            0.0
+        } else {
+            self.oov_penalty
+        }
+    }
+}
+
+impl LanguageModel for Lexicon {
+    fn log_prob(&self, context: &[char], ch: char) -> f32 {
+        let word = Self::current_word(context);
+        if ch.is_whitespace() {
+            return self.word_end_score(&word);
+        }
+
+        // 只在词首次偏离词典的字符处惩罚一次
+        let was_valid = word.is_empty() || self.prefixes.contains(&word);
+        let mut extended = word;
+        extended.push(ch);
+        if was_valid && !self.prefixes.contains(&extended) {
+            self.oov_penalty
+        } else {
+            0.0
+        }
+    }
+
+    fn end_log_prob(&self, context: &[char]) -> f32 {
+        self.word_end_score(&Self::current_word(context))
+    }
+}
+
+/// 字符级 n-gram 语言模型，可从 ARPA 文件加载，或从纯文本语料统计得到
+///
+/// Character n-gram language model, loaded from an ARPA file or estimated from a plain text corpus
+#[derive(Debug, Clone)]
+pub struct CharNgramLm {
+    order: usize,
+    // n-gram -> (对数概率, 回退权重)，均为自然对数
+    // n-gram -> (log probability, backoff weight), both in natural log
+    table: HashMap<String, (f32, f32)>,
+    unk_log_prob: f32,
+}
+
+impl CharNgramLm {
+    /// 从纯文本语料统计时的默认阶数
+    /// Default order when estimating from a plain text corpus
+    pub const ORDER_DEFAULT: usize = 3;
+
+    /// 句首标记
+    const BOS: char = '\u{E000}';
+    /// 句尾标记
+    const EOS: char = '\u{E001}';
+
+    /// 从纯文本语料统计模型，每行作为一个句子，未出现的 n-gram 使用 stupid backoff 回退
+    ///
+    /// Estimate a model from a plain text corpus with one sentence per line; unseen n-grams
+    /// fall back with stupid backoff
+    pub fn from_text(corpus: &str, order: usize) -> Self {
+        const BACKOFF: f32 = 0.4;
+
+        let order = order.max(1);
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        let mut context_counts: HashMap<String, u32> = HashMap::new();
+        let mut total = 0u32;
+
+        for line in corpus.lines().filter(|line| !line.trim().is_empty()) {
+            let chars: Vec<char> = std::iter::once(Self::BOS)
+                .chain(line.chars())
+                .chain(std::iter::once(Self::EOS))
+                .collect();
+
+            for end in 1..chars.len() {
+                total += 1;
+                for n in 1..=order.min(end + 1) {
+                    let gram: String = chars[end + 1 - n..=end].iter().collect();
+                    *counts.entry(gram).or_default() += 1;
+                    if n > 1 {
+                        let context: String = chars[end + 1 - n..end].iter().collect();
+                        *context_counts.entry(context).or_default() += 1;
+                    }
+                }
+            }
+        }
+
+        let vocab = counts
+            .keys()
+            .filter(|gram| gram.chars().count() == 1)
+            .count() as f32;
+        let denominator = total as f32 + vocab + 1.0;
+
+        let table = counts
+            .iter()
+            .map(|(gram, &count)| {
+                let mut chars = gram.chars();
+                chars.next_back();
+                let context = chars.as_str();
+                let log_prob = if context.is_empty() {
+                    ((count as f32 + 1.0) / denominator).ln()
+                } else {
+                    (count as f32 / context_counts[context] as f32).ln()
+                };
+                let backoff = if context_counts.contains_key(gram.as_str()) {
+                    BACKOFF.ln()
+                } else {
+                    0.0
+                };
+                (gram.clone(), (log_prob, backoff))
+            })
+            .collect();
+
+        Self {
+            order,
+            table,
+            unk_log_prob: (1.0 / denominator).ln(),
+        }
+    }
+
+    /// 解析 ARPA 格式的字符级语言模型，每个词元为单个字符，`<space>` 表示空格
+    ///
+    /// Parse a character-level language model in ARPA format; every token is a single
+    /// character and `<space>` stands for a space
+    pub fn from_arpa_str(content: &str) -> OcrResult<Self> {
+        let mut order = 0;
+        let mut current = None;
+        let mut table = HashMap::new();
+        let mut unk_log_prob = None;
+
+        for (line_no, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line == "\\data\\" || line.starts_with("ngram ") {
+                continue;
+            }
+            if line == "\\end\\" {
+                break;
+            }
+            if let Some(n) = line
+                .strip_prefix('\\')
+                .and_then(|rest| rest.strip_suffix("-grams:"))
+            {
+                let n = n.parse::<usize>().map_err(|_| {
+                    OcrError::InputError(format!("Invalid ARPA section at line {}", line_no + 1))
+                })?;
+                order = order.max(n);
+                current = Some(n);
+                continue;
+            }
+
+            let Some(n) = current else {
+                continue;
+            };
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() != n + 1 && fields.len() != n + 2 {
+                return Err(OcrError::InputError(format!(
+                    "Invalid ARPA entry at line {}: {}",
+                    line_no + 1,
+                    line
+                )));
+            }
+
+            let parse = |value: &str| {
+                value
+                    .parse::<f32>()
+                    .map(|x| x * std::f32::consts::LN_10)
+                    .map_err(|_| {
+                        OcrError::InputError(format!("Invalid ARPA number at line {}", line_no + 1))
+                    })
+            };
+            let log_prob = parse(fields[0])?;
+            let backoff = fields.get(n + 1).map_or(Ok(0.0), |x| parse(x))?;
+
+            if n == 1 && fields[1] == "<unk>" {
+                unk_log_prob = Some(log_prob);
+                continue;
+            }
+
+            let mut gram = String::new();
+            for token in &fields[1..=n] {
+                gram.push(Self::parse_token(token).ok_or_else(|| {
+                    OcrError::InputError(format!(
+                        "Token \"{}\" at line {} is not a single character",
+                        token,
+                        line_no + 1
+                    ))
+                })?);
+            }
+            table.insert(gram, (log_prob, backoff));
+        }
+
+        if table.is_empty() {
+            return Err(OcrError::InputError(
+                "Empty ARPA language model".to_string(),
+            ));
+        }
+
+        // 未提供 <unk> 时使用最小的一元概率
+        let unk_log_prob = unk_log_prob.unwrap_or_else(|| {
+            table
+                .iter()
+                .filter(|(gram, _)| gram.chars().count() == 1)
+                .map(|(_, &(log_prob, _))| log_prob)
+                .fold(0.0, f32::min)
+        });
+
+        Ok(Self {
+            order,
+            table,
+            unk_log_prob,
+        })
+    }
+
+    /// 从文件加载：以 `\data\` 开头时按 ARPA 格式解析，否则作为纯文本语料统计
+    ///
+    /// Load from a file: parsed as ARPA when it starts with `\data\`, otherwise estimated as a
+    /// plain text corpus
+    pub fn from_file(path: impl AsRef<Path>) -> OcrResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        if content.trim_start().starts_with("\\data\\") {
+            Self::from_arpa_str(&content)
+        } else {
+            Ok(Self::from_text(&content, Self::ORDER_DEFAULT))
+        }
+    }
+
+    /// 模型阶数
+    ///
+    /// Order of the model
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    fn parse_token(token: &str) -> Option<char> {
+        match token {
+            "<s>" => Some(Self::BOS),
+            "</s>" => Some(Self::EOS),
+            "<space>" => Some(' '),
+            _ => {
+                let mut chars = token.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) => Some(ch),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// 按回退规则计算 `history` 之后出现 `ch` 的对数概率
+    fn score(&self, context: &[char], ch: char) -> f32 {
+        let keep = self.order.saturating_sub(1).min(context.len() + 1);
+        let history: Vec<char> = std::iter::once(Self::BOS)
+            .chain(context.iter().copied())
+            .collect();
+        let history = &history[history.len() - keep..];
+
+        let mut backoff = 0.0;
+        for start in 0..=history.len() {
+            let context: String = history[start..].iter().collect();
+            let mut gram = context.clone();
+            gram.push(ch);
+            if let Some(&(log_prob, _)) = self.table.get(&gram) {
+                return backoff + log_prob;
+            }
+            if let Some(&(_, weight)) = self.table.get(&context) {
+                backoff += weight;
+            }
+        }
+
+        backoff + self.unk_log_prob
+    }
+}
+
+impl LanguageModel for CharNgramLm {
+    fn log_prob(&self, context: &[char], ch: char) -> f32 {
+        self.score(context, ch)
+    }
+
+    fn end_log_prob(&self, context: &[char]) -> f32 {
+        self.score(context, Self::EOS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lexicon_penalty() {
+        let lexicon = Lexicon::new(["BOLT", "M8"]);
+        let penalty = Lexicon::OOV_PENALTY_DEFAULT;
+
+        assert_eq!(lexicon.log_prob(&[], 'B'), 0.0);
+        assert_eq!(lexicon.log_prob(&['B'], 'O'), 0.0);
+        assert_eq!(lexicon.log_prob(&['B'], '0'), penalty);
+        // 已偏离的词不重复惩罚
+        assert_eq!(lexicon.log_prob(&['B', '0'], 'L'), 0.0);
+        // 不完整的词在结束时惩罚
+        assert_eq!(lexicon.end_log_prob(&['B', 'O']), penalty);
+        assert_eq!(lexicon.log_prob(&['M', '8'], ' '), 0.0);
+        assert_eq!(
+            lexicon.end_log_prob(&['M', '8', ' ', 'B', 'O', 'L', 'T']),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_arpa_backoff() {
+        let arpa = "\\data\\\nngram 1=4\nngram 2=2\n\n\\1-grams:\n-1.0 <s> -0.5\n-0.5 a -0.3\n-0.7 b\n-0.9 </s>\n\n\\2-grams:\n-0.1 <s> a\n-0.2 a b\n\n\\end\\\n";
+        let lm = CharNgramLm::from_arpa_str(arpa).unwrap();
+        let ln10 = std::f32::consts::LN_10;
+
+        assert_eq!(lm.order(), 2);
+        assert!((lm.log_prob(&[], 'a') - (-0.1 * ln10)).abs() < 1e-5);
+        assert!((lm.log_prob(&['a'], 'b') - (-0.2 * ln10)).abs() < 1e-5);
+        // p(a|a) = bow(a) + p(a)
+        assert!((lm.log_prob(&['a'], 'a') - (-0.8 * ln10)).abs() < 1e-5);
+        assert!((lm.end_log_prob(&['b']) - (-0.9 * ln10)).abs() < 1e-5);
+
+        assert!(CharNgramLm::from_arpa_str("\\data\\\n\\1-grams:\n-1.0 ab\n").is_err());
+    }
+
+    #[test]
+    fn test_from_text() {
+        let lm = CharNgramLm::from_text("M8 BOLT\nM10 BOLT\n", 3);
+        assert!(lm.log_prob(&['B', 'O'], 'L') > lm.log_prob(&['B', 'O'], '0'));
+        assert!(lm.log_prob(&['M'], '1') > lm.log_prob(&['M'], 'I'));
+    }
+}
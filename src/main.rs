@@ -1,8 +1,12 @@
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
 use log::{error, info};
-use rust_paddle_ocr::{OcrEngineManager, OcrError, OcrResult};
+use rust_paddle_ocr::{
+    CharFilter, CharNgramLm, CharSet, CtcDecoder, LanguageModel, Lexicon, ModelBundle, ModelDir,
+    ModelInfo, ModelProfile, OcrEngineConfig, OcrEngineManager, OcrError, OcrResult, Pattern,
+    Precision, RecognizeOptions, RuntimeOptions, ScoreAggregation, TensorInfo,
+};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc};
 
 // 使用v5版本的模型
 mod models {
@@ -25,8 +29,16 @@ enum OutputMode {
 
 // 命令行参数
 #[derive(Parser, Debug)]
-#[command(author, version, about = "PaddleOCR command line tool")]
+#[command(
+    author,
+    version,
+    about = "PaddleOCR command line tool",
+    args_conflicts_with_subcommands = true
+)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// 要识别的图像路径
     #[arg(short, long, value_name = "IMAGE_PATH")]
     path: Option<PathBuf>,
@@ -42,6 +54,163 @@ struct Args {
     /// 显示模型版本信息
     #[arg(long)]
     version_info: bool,
+
+    /// 可选的文本行方向分类模型路径，用于校正上下颠倒的文本行
+    #[arg(long, value_name = "CLS_MODEL_PATH")]
+    cls_model: Option<PathBuf>,
+
+    /// 识别前自动校正页面方向（0°/90°/180°/270°）
+    #[arg(long)]
+    auto_rotate: bool,
+
+    /// 可选的文档方向分类模型路径，设置后自动启用页面方向校正
+    #[arg(long, value_name = "DOC_ORI_MODEL_PATH")]
+    doc_ori_model: Option<PathBuf>,
+
+    /// 使用束搜索解码，指定束宽
+    #[arg(long, value_name = "BEAM_WIDTH")]
+    beam_width: Option<usize>,
+
+    /// 可选的词典文件（每行一个词），启用束搜索并偏向词典中的词
+    #[arg(long, value_name = "LEXICON_PATH", conflicts_with = "lm")]
+    lexicon: Option<PathBuf>,
+
+    /// 可选的字符级语言模型文件（ARPA 格式或纯文本语料），启用束搜索
+    #[arg(long, value_name = "LM_PATH")]
+    lm: Option<PathBuf>,
+
+    /// 词典或语言模型的权重
+    #[arg(long, default_value_t = CtcDecoder::LM_WEIGHT_DEFAULT)]
+    lm_weight: f32,
+
+    /// 只允许输出的字符：预定义类别（digits、uppercase、lowercase、letters、alphanumeric、ascii、han）
+    /// 或以 chars: 开头的自定义字符（如 chars:0123456789.-），可重复指定
+    #[arg(long, value_name = "CHARSET")]
+    allow: Vec<CharSet>,
+
+    /// 禁止输出的字符，格式同 --allow，可重复指定
+    #[arg(long, value_name = "CHARSET")]
+    deny: Vec<CharSet>,
+
+    /// 用正则表达式约束每行的识别结果（需完整匹配）
+    #[arg(long, value_name = "REGEX", conflicts_with = "mask")]
+    pattern: Option<String>,
+
+    /// 用格式掩码约束每行的识别结果：0 数字、A 字母、* 字母或数字、? 任意字符，例如 AA-0000-A
+    #[arg(long, value_name = "MASK")]
+    mask: Option<String>,
+
+    /// 模式匹配结果的最低平均置信度
+    #[arg(long, default_value_t = 0.0)]
+    pattern_min_score: f32,
+
+    /// 字符的最小置信度，低于该值的字符会被标记为不确定，默认 0.6
+    #[arg(long)]
+    min_score: Option<f32>,
+
+    /// 文本行置信度的计算方式：mean（默认）、min 或 geometric-mean
+    #[arg(long, value_name = "AGGREGATION")]
+    score_aggregation: Option<ScoreAggregation>,
+
+    /// 推理线程数，默认由 MNN 决定
+    #[arg(long, value_name = "THREADS")]
+    threads: Option<u32>,
+
+    /// 推理精度：normal、high 或 low
+    #[arg(long, value_name = "PRECISION", default_value = "low")]
+    precision: Precision,
+
+    /// 使用单文件模型包代替内置模型
+    #[arg(long, value_name = "BUNDLE_PATH", conflicts_with = "model_dir")]
+    bundle: Option<PathBuf>,
+
+    /// 从模型目录加载模型，目录中可包含 manifest.json 清单
+    #[arg(long, value_name = "MODEL_DIR")]
+    model_dir: Option<PathBuf>,
+}
+
+// 子命令
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// 查看 MNN 模型的输入输出张量，并检查模型是否与字典或模型类型相符
+    Inspect(InspectArgs),
+    /// 将检测、识别模型和字典打包为单文件模型包
+    Bundle(BundleArgs),
+}
+
+// bundle 子命令的参数
+#[derive(clap::Args, Debug)]
+struct BundleArgs {
+    /// 检测模型路径
+    #[arg(long, value_name = "DET_MODEL_PATH")]
+    det: PathBuf,
+
+    /// 识别模型路径
+    #[arg(long, value_name = "REC_MODEL_PATH")]
+    rec: PathBuf,
+
+    /// 字典路径
+    #[arg(long, value_name = "KEYS_PATH")]
+    keys: PathBuf,
+
+    /// 可选的文本行方向分类模型路径
+    #[arg(long, value_name = "CLS_MODEL_PATH")]
+    cls: Option<PathBuf>,
+
+    /// 模型对应的 PaddleOCR 版本
+    #[arg(long, value_name = "PROFILE", default_value = "v5")]
+    profile: ModelProfile,
+
+    /// 输出的模型包路径
+    #[arg(short, long, value_name = "OUTPUT_PATH")]
+    output: PathBuf,
+}
+
+// inspect 子命令的参数
+#[derive(clap::Args, Debug)]
+struct InspectArgs {
+    /// 要查看的模型路径
+    #[arg(value_name = "MODEL_PATH")]
+    model: PathBuf,
+
+    /// 识别模型的字典路径，指定后检查模型输出的类别数是否与字典一致
+    #[arg(long, value_name = "KEYS_PATH", conflicts_with = "det")]
+    keys: Option<PathBuf>,
+
+    /// 按文本检测模型检查输出通道数
+    #[arg(long)]
+    det: bool,
+
+    /// 模型对应的 PaddleOCR 版本，决定识别输出的维度顺序
+    #[arg(long, value_name = "PROFILE", default_value = "v5")]
+    profile: ModelProfile,
+
+    /// 输出模式：json(详细) 或 text(简单)
+    #[arg(short, long, value_enum, default_value_t = OutputMode::Text)]
+    mode: OutputMode,
+}
+
+// 模型信息的JSON表示
+#[derive(Serialize, Deserialize)]
+struct ModelReport {
+    inputs: Vec<TensorReport>,
+    outputs: Vec<TensorReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    check: Option<CheckReport>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TensorReport {
+    name: String,
+    shape: Vec<i32>,
+    data_type: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CheckReport {
+    passed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
 }
 
 // 文本识别结果的JSON表示
@@ -50,6 +219,12 @@ struct TextBox {
     text: String,
     confidence: f32,
     position: TextBoxPosition,
+    vertical: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    matches_pattern: Option<bool>,
+    /// 置信度低于阈值的字符在文本中的位置
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    uncertain_chars: Vec<usize>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -60,10 +235,82 @@ struct TextBoxPosition {
     height: u32,
 }
 
+// 输出模型信息，并按参数检查模型
+fn inspect(args: &InspectArgs) -> OcrResult<()> {
+    let info = ModelInfo::from_file(&args.model)?;
+    let check = if let Some(keys) = &args.keys {
+        let decoder = CtcDecoder::from_dict_str(&std::fs::read_to_string(keys)?);
+        Some(info.check_rec(decoder.num_classes(), args.profile.rec_output_layout))
+    } else if args.det {
+        Some(info.check_det())
+    } else {
+        None
+    };
+
+    match args.mode {
+        OutputMode::Json => {
+            let tensors = |tensors: &[TensorInfo]| {
+                tensors
+                    .iter()
+                    .map(|x| TensorReport {
+                        name: x.name.clone(),
+                        shape: x.shape.clone(),
+                        data_type: x.data_type.to_string(),
+                    })
+                    .collect()
+            };
+            let report = ModelReport {
+                inputs: tensors(&info.inputs),
+                outputs: tensors(&info.outputs),
+                check: check.as_ref().map(|result| CheckReport {
+                    passed: result.is_ok(),
+                    error: result.as_ref().err().map(|e| e.to_string()),
+                }),
+            };
+            let json = serde_json::to_string_pretty(&report)
+                .map_err(|e| OcrError::OutputError(e.to_string()))?;
+            println!("{}", json);
+        }
+        OutputMode::Text => {
+            print!("{}", info);
+            if let Some(Ok(())) = &check {
+                println!("check: ok");
+            }
+        }
+    }
+
+    check.unwrap_or(Ok(()))
+}
+
+// 打包模型包，写入前先检查模型与字典是否匹配
+fn bundle(args: &BundleArgs) -> OcrResult<()> {
+    ModelInfo::from_file(&args.det)?.check_det()?;
+    let keys = std::fs::read_to_string(&args.keys)?;
+    ModelInfo::from_file(&args.rec)?.check_rec(
+        CtcDecoder::from_dict_str(&keys).num_classes(),
+        args.profile.rec_output_layout,
+    )?;
+
+    let mut bundle =
+        ModelBundle::from_files(&args.det, &args.rec, &args.keys)?.with_profile(args.profile);
+    if let Some(cls) = &args.cls {
+        bundle = bundle.with_cls(std::fs::read(cls)?);
+    }
+    bundle.save(&args.output)?;
+    println!("Model bundle written to {}", args.output.display());
+    Ok(())
+}
+
 fn main() -> OcrResult<()> {
     // 解析命令行参数
     let args = Args::parse();
 
+    match &args.command {
+        Some(Command::Inspect(inspect_args)) => return inspect(inspect_args),
+        Some(Command::Bundle(bundle_args)) => return bundle(bundle_args),
+        None => {}
+    }
+
     // 如果请求版本信息，则显示并退出
     if args.version_info {
         println!("PaddleOCR CLI - Model Version: PP-OCR{}", models::VERSION);
@@ -105,16 +352,107 @@ fn main() -> OcrResult<()> {
 }
 
 fn process_ocr(args: &Args, image_path: &PathBuf) -> OcrResult<()> {
-    // 直接使用字节数据初始化OCR引擎
-    info!(
-        "Initializing OCR engine from embedded PP-OCR{} models...",
-        models::VERSION
-    );
-    OcrEngineManager::initialize_with_config_and_bytes(
-        DET_MODEL, REC_MODEL, KEYS_DATA, 12,    // rect_border_size
-        false, // merge_boxes
-        1,     // merge_threshold
-    )?;
+    let mut config = OcrEngineConfig::default()
+        .with_rect_border_size(12)
+        .with_merge_boxes(false)
+        .with_merge_threshold(1);
+
+    // 先应用模型目录清单中的默认参数，命令行参数在其之上覆盖
+    let model_dir = args.model_dir.as_ref().map(ModelDir::open).transpose()?;
+    if let Some(model_dir) = &model_dir {
+        config = model_dir.apply_to(config);
+    }
+
+    if let Some(cls_model) = &args.cls_model {
+        info!("Using direction classifier from {:?}", cls_model);
+        config = config.with_cls_model(cls_model.clone());
+    }
+    if args.auto_rotate {
+        config = config.with_auto_rotate(true);
+    }
+    if let Some(doc_ori_model) = &args.doc_ori_model {
+        info!(
+            "Using document orientation classifier from {:?}",
+            doc_ori_model
+        );
+        config = config.with_doc_ori_model(doc_ori_model.clone());
+    }
+    if let Some(beam_width) = args.beam_width {
+        config = config.with_beam_search(beam_width);
+    }
+    let lm: Option<Arc<dyn LanguageModel>> = match (&args.lexicon, &args.lm) {
+        (Some(lexicon), _) => {
+            info!("Using lexicon from {:?}", lexicon);
+            Some(Arc::new(Lexicon::from_file(lexicon)?))
+        }
+        (None, Some(lm)) => {
+            info!("Using language model from {:?}", lm);
+            Some(Arc::new(CharNgramLm::from_file(lm)?))
+        }
+        (None, None) => None,
+    };
+    if let Some(lm) = lm {
+        config = config.with_language_model(lm, args.lm_weight);
+    }
+    let char_filter = args
+        .allow
+        .iter()
+        .cloned()
+        .fold(CharFilter::new(), CharFilter::allow);
+    let char_filter = args
+        .deny
+        .iter()
+        .cloned()
+        .fold(char_filter, CharFilter::deny);
+    config = config.with_char_filter(char_filter);
+    if let Some(min_score) = args.min_score {
+        config = config.with_min_score(min_score);
+    }
+    if let Some(score_aggregation) = args.score_aggregation {
+        config = config.with_score_aggregation(score_aggregation);
+    }
+    let mut runtime = RuntimeOptions::default().with_precision(args.precision);
+    if let Some(threads) = args.threads {
+        runtime = runtime.with_threads(threads);
+    }
+    config = config.with_runtime(runtime);
+    if let Some(model_dir) = &model_dir {
+        info!(
+            "Initializing OCR engine from model directory {:?}...",
+            args.model_dir.as_ref().unwrap()
+        );
+        OcrEngineManager::initialize_with_options(
+            &model_dir.det_model,
+            &model_dir.rec_model,
+            &model_dir.keys,
+            config,
+        )?;
+    } else if let Some(bundle) = &args.bundle {
+        info!("Initializing OCR engine from bundle {:?}...", bundle);
+        OcrEngineManager::initialize_from_bundle_with_options(bundle, config)?;
+    } else {
+        // 直接使用字节数据初始化OCR引擎
+        info!(
+            "Initializing OCR engine from embedded PP-OCR{} models...",
+            models::VERSION
+        );
+        OcrEngineManager::initialize_with_options_and_bytes(
+            DET_MODEL, REC_MODEL, KEYS_DATA, config,
+        )?;
+    }
+
+    let pattern = match (&args.pattern, &args.mask) {
+        (Some(regex), _) => Some(Pattern::regex(regex)?),
+        (None, Some(mask)) => Some(Pattern::mask(mask)),
+        (None, None) => None,
+    };
+    let options = match pattern {
+        Some(pattern) => {
+            info!("Constraining recognition to pattern {:?}", pattern.as_str());
+            RecognizeOptions::default().with_pattern(pattern, args.pattern_min_score)
+        }
+        None => RecognizeOptions::default(),
+    };
 
     // 加载图像
     info!("Loading image from {:?}...", image_path);
@@ -134,61 +472,39 @@ fn process_ocr(args: &Args, image_path: &PathBuf) -> OcrResult<()> {
         OutputMode::Json => {
             info!("Processing in JSON mode...");
 
-            // 获取文本区域矩形框
-            let text_rects = OcrEngineManager::get_text_rects(&img)?;
-            info!("Found {} text regions", text_rects.len());
-
-            if text_rects.is_empty() {
-                info!("No text regions detected in the image.");
-                println!("[]");
-                return Ok(());
-            }
-
-            // 获取文本区域图像
-            let text_images = OcrEngineManager::get_text_images(&img)?;
-            info!("Successfully extracted {} text images", text_images.len());
-
-            // 确保文本区域和图像数量一致
-            if text_rects.len() != text_images.len() {
-                error!(
-                    "Mismatch between text rectangles ({}) and text images ({})",
-                    text_rects.len(),
-                    text_images.len()
+            // 完整OCR处理，获取每行文本的位置和置信度
+            let page = OcrEngineManager::process_ocr_with_options(img, options)?;
+            info!("Found {} text regions", page.lines.len());
+            if page.angle != 0 {
+                info!(
+                    "Page was rotated by {} degrees and has been turned upright",
+                    page.angle
                 );
-                return Err(OcrError::EngineError(
-                    "Inconsistent detection results".to_string(),
-                ));
             }
 
-            let mut results = Vec::new();
-
-            for (i, (rect, text_img)) in text_rects.iter().zip(text_images.iter()).enumerate() {
-                info!("Processing text region {} of {}", i + 1, text_rects.len());
-
-                // 检查子图像是否有效
-                if text_img.width() == 0 || text_img.height() == 0 {
-                    error!("Invalid subimage with zero dimensions at index {}", i);
-                    continue;
-                }
-
-                match OcrEngineManager::recognize_text(text_img.clone()) {
-                    Ok(text) => {
-                        results.push(TextBox {
-                            text,
-                            confidence: 1.0, // 使用引擎管理器无法获取置信度，设为默认值
-                            position: TextBoxPosition {
-                                left: rect.left(),
-                                top: rect.top(),
-                                width: rect.width(),
-                                height: rect.height(),
-                            },
-                        });
-                    }
-                    Err(e) => {
-                        error!("Failed to recognize text in region {}: {}", i, e);
-                    }
-                }
-            }
+            let results: Vec<TextBox> = page
+                .lines
+                .into_iter()
+                .map(|line| TextBox {
+                    uncertain_chars: line
+                        .chars
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, x)| x.uncertain)
+                        .map(|(i, _)| i)
+                        .collect(),
+                    text: line.text,
+                    confidence: line.score,
+                    position: TextBoxPosition {
+                        left: line.rect.left(),
+                        top: line.rect.top(),
+                        width: line.rect.width(),
+                        height: line.rect.height(),
+                    },
+                    vertical: line.vertical,
+                    matches_pattern: line.matches_pattern,
+                })
+                .collect();
 
             // 输出JSON结果
             let json = serde_json::to_string_pretty(&results)
@@ -199,8 +515,13 @@ fn process_ocr(args: &Args, image_path: &PathBuf) -> OcrResult<()> {
         OutputMode::Text => {
             info!("Processing in text mode...");
 
-            // 直接使用完整的OCR处理
-            let texts = OcrEngineManager::process_ocr(img)?;
+            // 直接使用完整的OCR处理，设置了模式时按模式约束识别
+            let texts = if options.pattern.is_some() {
+                let page = OcrEngineManager::process_ocr_with_options(img, options)?;
+                page.lines.into_iter().map(|line| line.text).collect()
+            } else {
+                OcrEngineManager::process_ocr(img)?
+            };
 
             for text in texts {
                 println!("{}", text);
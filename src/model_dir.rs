@@ -0,0 +1,395 @@
+use serde::{de, Deserialize, Deserializer};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::det::LimitType;
+use crate::engine::OcrEngineConfig;
+use crate::error::{OcrError, OcrResult};
+use crate::profile::ModelProfile;
+use crate::result::ScoreAggregation;
+
+/// 模型目录：由目录中的清单文件，或按 PaddleOCR 的文件命名约定找到的模型、字典和默认参数
+///
+/// 清单为 JSON 文件，所有字段均可省略，省略的模型和字典按命名约定查找，路径相对于目录：
+///
+/// Model directory: the models, dictionary and default options found through the manifest file
+/// in the directory, or through PaddleOCR's file naming conventions
+///
+/// The manifest is a JSON file whose fields are all optional; omitted models and dictionaries
+/// are looked up by naming convention, and paths are relative to the directory:
+///
+/// ```json
+/// {
+///     "det_model": "PP-OCRv5_server_det.mnn",
+///     "rec_model": "PP-OCRv5_server_rec.mnn",
+///     "keys": "ppocr_keys_v5.txt",
+///     "cls_model": "PP-LCNet_x1_0_textline_ori.mnn",
+///     "profile": "v5-server",
+///     "det": { "thresh": 0.3, "box_thresh": 0.6, "limit_side_len": 960, "limit_type": "max" },
+///     "rec": { "batch_size": 8, "min_score": 0.5, "score_aggregation": "mean" }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ModelDir {
+    /// 检测模型路径
+    /// Detection model path
+    pub det_model: PathBuf,
+    /// 识别模型路径
+    /// Recognition model path
+    pub rec_model: PathBuf,
+    /// 字典路径
+    /// Dictionary path
+    pub keys: PathBuf,
+    /// 可选的文本行方向分类模型路径
+    /// Optional text line direction classification model path
+    pub cls_model: Option<PathBuf>,
+    /// 可选的文档方向分类模型路径
+    /// Optional document orientation classification model path
+    pub doc_ori_model: Option<PathBuf>,
+    /// 清单或文件名给出的模型预处理和输出配置，为 `None` 时沿用引擎配置中的设置
+    /// Preprocessing and output profile of the models given by the manifest or the file names;
+    /// `None` keeps the one in the engine configuration
+    pub profile: Option<ModelProfile>,
+    cls_thresh: Option<f32>,
+    det: DetManifest,
+    rec: RecManifest,
+}
+
+/// 模型目录的清单
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Manifest {
+    det_model: Option<PathBuf>,
+    rec_model: Option<PathBuf>,
+    keys: Option<PathBuf>,
+    cls_model: Option<PathBuf>,
+    doc_ori_model: Option<PathBuf>,
+    #[serde(deserialize_with = "parse_str")]
+    profile: Option<ModelProfile>,
+    cls_thresh: Option<f32>,
+    det: DetManifest,
+    rec: RecManifest,
+}
+
+/// 清单中的检测参数
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct DetManifest {
+    thresh: Option<f32>,
+    box_thresh: Option<f32>,
+    unclip_ratio: Option<f32>,
+    min_box_size: Option<u32>,
+    max_box_size: Option<u32>,
+    limit_side_len: Option<u32>,
+    #[serde(deserialize_with = "parse_str")]
+    limit_type: Option<LimitType>,
+}
+
+/// 清单中的识别参数
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RecManifest {
+    batch_size: Option<usize>,
+    beam_width: Option<usize>,
+    min_score: Option<f32>,
+    #[serde(deserialize_with = "parse_str")]
+    score_aggregation: Option<ScoreAggregation>,
+}
+
+impl ModelDir {
+    /// 清单文件名
+    /// Manifest file name
+    pub const MANIFEST_FILE: &'static str = "manifest.json";
+
+    /// 读取模型目录，有清单时按清单加载，否则按 PaddleOCR 的文件命名约定查找
+    ///
+    /// Read a model directory, following its manifest if present and PaddleOCR's file naming
+    /// conventions otherwise
+    pub fn open(dir: impl AsRef<Path>) -> OcrResult<Self> {
+        let dir = dir.as_ref();
+        let manifest_path = dir.join(Self::MANIFEST_FILE);
+        let manifest: Manifest = if manifest_path.is_file() {
+            serde_json::from_str(&std::fs::read_to_string(&manifest_path)?)
+                .map_err(|e| OcrError::JsonError(format!("{}: {}", manifest_path.display(), e)))?
+        } else {
+            Manifest::default()
+        };
+
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_file() {
+                files.push(path);
+            }
+        }
+        files.sort();
+
+        let resolve = |name: Option<PathBuf>, kind: FileKind| -> OcrResult<Option<PathBuf>> {
+            match name {
+                Some(name) => {
+                    let path = dir.join(name);
+                    if path.is_file() {
+                        Ok(Some(path))
+                    } else {
+                        Err(OcrError::InputError(format!(
+                            "{} listed in the manifest does not exist: {}",
+                            kind,
+                            path.display()
+                        )))
+                    }
+                }
+                None => find_file(&files, kind),
+            }
+        };
+        let require = |path: Option<PathBuf>, kind: FileKind| {
+            path.ok_or_else(|| {
+                OcrError::InputError(format!("No {} found in {}", kind, dir.display()))
+            })
+        };
+
+        let det_model = require(resolve(manifest.det_model, FileKind::Det)?, FileKind::Det)?;
+        let rec_model = require(resolve(manifest.rec_model, FileKind::Rec)?, FileKind::Rec)?;
+        let keys = require(resolve(manifest.keys, FileKind::Keys)?, FileKind::Keys)?;
+        let cls_model = resolve(manifest.cls_model, FileKind::Cls)?;
+        let doc_ori_model = resolve(manifest.doc_ori_model, FileKind::DocOri)?;
+        let profile = manifest.profile.or_else(|| infer_profile(&rec_model));
+
+        Ok(Self {
+            det_model,
+            rec_model,
+            keys,
+            cls_model,
+            doc_ori_model,
+            profile,
+            cls_thresh: manifest.cls_thresh,
+            det: manifest.det,
+            rec: manifest.rec,
+        })
+    }
+
+    /// 将目录中的模型配置和清单中的默认参数应用到引擎配置，目录未给出模型配置时保留引擎配置中的
+    /// 设置
+    ///
+    /// Apply the directory's model profile and the manifest's default options to an engine
+    /// configuration; the configuration's profile is kept when the directory does not give one
+    pub fn apply_to(&self, config: OcrEngineConfig) -> OcrEngineConfig {
+        let mut config = match self.profile {
+            Some(profile) => config.with_model_profile(profile),
+            None => config,
+        };
+        if let Some(cls_model) = &self.cls_model {
+            config = config.with_cls_model(cls_model.clone());
+        }
+        if let Some(doc_ori_model) = &self.doc_ori_model {
+            config = config.with_doc_ori_model(doc_ori_model.clone());
+        }
+        if let Some(cls_thresh) = self.cls_thresh {
+            config = config.with_cls_thresh(cls_thresh);
+        }
+
+        let det = &self.det;
+        let mut options = config.det_options;
+        if let Some(thresh) = det.thresh {
+            options = options.with_thresh(thresh);
+        }
+        if let Some(box_thresh) = det.box_thresh {
+            options = options.with_box_thresh(box_thresh);
+        }
+        if let Some(unclip_ratio) = det.unclip_ratio {
+            options = options.with_unclip_ratio(unclip_ratio);
+        }
+        if let Some(min_box_size) = det.min_box_size {
+            options = options.with_min_box_size(min_box_size);
+        }
+        if let Some(max_box_size) = det.max_box_size {
+            options = options.with_max_box_size(max_box_size);
+        }
+        if let Some(limit_side_len) = det.limit_side_len.or(options.limit_side_len) {
+            let limit_type = det.limit_type.unwrap_or(options.limit_type);
+            options = options.with_limit_side_len(limit_side_len, limit_type);
+        }
+        config = config.with_det_options(options);
+
+        let rec = &self.rec;
+        if let Some(batch_size) = rec.batch_size {
+            config = config.with_rec_batch_size(batch_size);
+        }
+        if let Some(beam_width) = rec.beam_width {
+            config = config.with_beam_search(beam_width);
+        }
+        if let Some(min_score) = rec.min_score {
+            config = config.with_min_score(min_score);
+        }
+        if let Some(score_aggregation) = rec.score_aggregation {
+            config = config.with_score_aggregation(score_aggregation);
+        }
+        config
+    }
+}
+
+/// 目录中的文件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileKind {
+    Det,
+    Rec,
+    Keys,
+    Cls,
+    DocOri,
+}
+
+impl fmt::Display for FileKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Det => "detection model",
+            Self::Rec => "recognition model",
+            Self::Keys => "dictionary",
+            Self::Cls => "text line orientation model",
+            Self::DocOri => "document orientation model",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FileKind {
+    /// 按 PaddleOCR 的命名约定判断文件类型，例如 `PP-OCRv5_mobile_det.mnn`、
+    /// `ch_PP-OCRv4_rec_infer.mnn`、`PP-LCNet_x1_0_textline_ori.mnn`、`ppocr_keys_v1.txt`
+    fn of(path: &Path) -> Option<Self> {
+        let name = path.file_stem()?.to_str()?.to_ascii_lowercase();
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        let has_token = |token: &str| name.split(['_', '-', '.']).any(|x| x == token);
+
+        match extension.as_str() {
+            "txt" if name.contains("keys") || name.contains("dict") => Some(Self::Keys),
+            "mnn" if name.contains("doc_ori") => Some(Self::DocOri),
+            "mnn" if name.contains("textline_ori") || has_token("cls") => Some(Self::Cls),
+            "mnn" if has_token("det") => Some(Self::Det),
+            "mnn" if has_token("rec") => Some(Self::Rec),
+            _ => None,
+        }
+    }
+}
+
+/// 按命名约定查找某类文件，找到多个时报错
+fn find_file(files: &[PathBuf], kind: FileKind) -> OcrResult<Option<PathBuf>> {
+    let mut matches = files.iter().filter(|x| FileKind::of(x) == Some(kind));
+    let first = matches.next();
+    if let Some(second) = matches.next() {
+        return Err(OcrError::InputError(format!(
+            "Found more than one {}: {} and {}; name it in {}",
+            kind,
+            first.unwrap().display(),
+            second.display(),
+            ModelDir::MANIFEST_FILE
+        )));
+    }
+    Ok(first.cloned())
+}
+
+/// 由模型文件名推断 PaddleOCR 版本，例如 `PP-OCRv4_server_rec` 对应 v4 服务端模型
+fn infer_profile(model: &Path) -> Option<ModelProfile> {
+    let name = model.file_stem()?.to_str()?.to_ascii_lowercase();
+    let version = ["v5", "v4", "v3"]
+        .into_iter()
+        .find(|v| name.contains(&format!("ocr{}", v)))
+        .or_else(|| name.contains("_v2").then_some("v2"))?;
+    let size = if name.contains("server") {
+        "server"
+    } else {
+        "mobile"
+    };
+
+    match version {
+        "v2" | "v3" => version.parse().ok(),
+        _ => format!("{}-{}", version, size).parse().ok(),
+    }
+}
+
+/// 用 `FromStr` 解析清单中的字符串字段
+fn parse_str<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| s.parse().map_err(de::Error::custom))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_naming_conventions() {
+        let kind = |name: &str| FileKind::of(Path::new(name));
+        assert_eq!(kind("PP-OCRv5_mobile_det_fp16.mnn"), Some(FileKind::Det));
+        assert_eq!(kind("ch_PP-OCRv4_rec_infer.mnn"), Some(FileKind::Rec));
+        assert_eq!(
+            kind("ch_ppocr_mobile_v2.0_cls_infer.mnn"),
+            Some(FileKind::Cls)
+        );
+        assert_eq!(kind("PP-LCNet_x1_0_textline_ori.mnn"), Some(FileKind::Cls));
+        assert_eq!(kind("PP-LCNet_x1_0_doc_ori.mnn"), Some(FileKind::DocOri));
+        assert_eq!(kind("ppocr_keys_v5.txt"), Some(FileKind::Keys));
+        assert_eq!(kind("readme.txt"), None);
+
+        let profile = |name: &str| infer_profile(Path::new(name));
+        assert_eq!(
+            profile("PP-OCRv4_server_rec.mnn"),
+            Some(ModelProfile::PP_OCRV4_SERVER)
+        );
+        assert_eq!(
+            profile("ch_ppocr_server_v2.0_rec_infer.mnn"),
+            Some(ModelProfile::PP_OCRV2)
+        );
+        assert_eq!(profile("rec.mnn"), None);
+    }
+
+    #[test]
+    fn test_manifest() {
+        let manifest: Manifest = serde_json::from_str(
+            r#"{"profile": "v2", "det": {"limit_side_len": 1280, "limit_type": "min"}}"#,
+        )
+        .unwrap();
+        assert_eq!(manifest.profile, Some(ModelProfile::PP_OCRV2));
+        assert_eq!(manifest.det.limit_type, Some(LimitType::Min));
+
+        assert!(serde_json::from_str::<Manifest>(r#"{"profile": "v9"}"#).is_err());
+        assert!(serde_json::from_str::<Manifest>(r#"{"det_modle": "det.mnn"}"#).is_err());
+    }
+
+    #[test]
+    fn test_profile_kept_without_hint() {
+        let dir = std::env::temp_dir().join(format!("rocr_model_dir_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["det.mnn", "rec.mnn", "keys.txt"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+
+        let model_dir = ModelDir::open(&dir).unwrap();
+        assert_eq!(model_dir.profile, None);
+        let config = OcrEngineConfig::default().with_model_profile(ModelProfile::PP_OCRV2);
+        assert_eq!(
+            model_dir.apply_to(config).model_profile,
+            ModelProfile::PP_OCRV2
+        );
+
+        std::fs::write(
+            dir.join(ModelDir::MANIFEST_FILE),
+            r#"{"profile": "v4-server"}"#,
+        )
+        .unwrap();
+        let model_dir = ModelDir::open(&dir).unwrap();
+        let config = OcrEngineConfig::default().with_model_profile(ModelProfile::PP_OCRV2);
+        assert_eq!(
+            model_dir.apply_to(config).model_profile,
+            ModelProfile::PP_OCRV4_SERVER
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
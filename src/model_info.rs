@@ -0,0 +1,295 @@
+use mnn::{Interpreter, Session, TensorList};
+use std::{fmt, path::Path};
+
+use crate::error::{OcrError, OcrResult};
+use crate::profile::OutputLayout;
+use crate::runtime::{ForwardType, Precision, RuntimeOptions};
+
+/// 张量的数据类型
+///
+/// Data type of a tensor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TensorDataType {
+    F32,
+    F64,
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    /// 无法识别的类型
+    /// Unrecognized type
+    Unknown,
+}
+
+impl fmt::Display for TensorDataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::F32 => "float32",
+            Self::F64 => "float64",
+            Self::I8 => "int8",
+            Self::I16 => "int16",
+            Self::I32 => "int32",
+            Self::I64 => "int64",
+            Self::U8 => "uint8",
+            Self::U16 => "uint16",
+            Self::Unknown => "unknown",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 模型输入或输出张量的描述
+///
+/// Description of a model input or output tensor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TensorInfo {
+    /// 张量名称
+    /// Tensor name
+    pub name: String,
+    /// 张量形状，动态维度为 -1
+    /// Tensor shape; dynamic dimensions are -1
+    pub shape: Vec<i32>,
+    /// 数据类型
+    /// Data type
+    pub data_type: TensorDataType,
+}
+
+impl fmt::Display for TensorInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} {:?}", self.name, self.data_type, self.shape)
+    }
+}
+
+/// MNN 模型的输入输出信息，用于排查模型或字典用错的问题
+///
+/// Input and output information of an MNN model, used to track down a wrong model or dictionary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelInfo {
+    /// 模型声明的输入张量
+    /// Input tensors as declared by the model
+    pub inputs: Vec<TensorInfo>,
+    /// 输出张量；输入含动态维度时，按探测尺寸推算输出形状
+    /// Output tensors; when the inputs have dynamic dimensions, output shapes are computed for
+    /// the probe size
+    pub outputs: Vec<TensorInfo>,
+}
+
+impl ModelInfo {
+    /// 推算输出形状时，动态的高和宽使用的尺寸
+    /// Size used for dynamic heights and widths when computing output shapes
+    pub const PROBE_SIZE: i32 = 640;
+
+    /// 读取模型文件的输入输出信息
+    ///
+    /// Read the input and output information of a model file
+    pub fn from_file(model_path: impl AsRef<Path>) -> OcrResult<Self> {
+        Self::from_interpreter(Interpreter::from_file(model_path)?)
+    }
+
+    /// 读取内存中模型的输入输出信息
+    ///
+    /// Read the input and output information of a model in memory
+    pub fn from_bytes(model_bytes: impl AsRef<[u8]>) -> OcrResult<Self> {
+        Self::from_interpreter(Interpreter::from_bytes(model_bytes)?)
+    }
+
+    fn from_interpreter(mut interpreter: Interpreter) -> OcrResult<Self> {
+        let runtime = RuntimeOptions::default()
+            .with_forward_type(ForwardType::CPU)
+            .with_precision(Precision::High);
+        let mut session = interpreter.create_session(runtime.schedule_config())?;
+        let inputs = tensor_infos(&interpreter.inputs(&session));
+
+        // 将动态维度替换为探测尺寸，使输出形状可以推算
+        if inputs.iter().any(|x| x.shape.iter().any(|&dim| dim <= 0)) {
+            for input in &inputs {
+                let shape: Vec<i32> = input
+                    .shape
+                    .iter()
+                    .enumerate()
+                    .map(|(index, &dim)| match index {
+                        _ if dim > 0 => dim,
+                        0 => 1,
+                        1 => 3,
+                        _ => Self::PROBE_SIZE,
+                    })
+                    .collect();
+                let mut tensor =
+                    unsafe { interpreter.input_unresized::<f32>(&session, &input.name)? };
+                interpreter.resize_tensor(&mut tensor, shape);
+            }
+            interpreter.resize_session(&mut session);
+        }
+
+        let outputs = tensor_infos(&interpreter.outputs(&session));
+        Ok(Self { inputs, outputs })
+    }
+
+    /// 检查是否为文本检测模型：输出应为单通道概率图 [批大小, 1, 高, 宽]
+    ///
+    /// Check that this is a text detection model, whose output should be a single-channel
+    /// probability map [batch, 1, height, width]
+    pub fn check_det(&self) -> OcrResult<()> {
+        self.check_image_input("detection")?;
+        let output = self.first_output("detection")?;
+        match output.shape.as_slice() {
+            [_, 1, _, _] => Ok(()),
+            [_, channels, _, _] => Err(OcrError::InputError(format!(
+                "Detection model output '{}' has {} channels, expected a single probability map",
+                output.name, channels
+            ))),
+            shape => Err(OcrError::InputError(format!(
+                "Detection model output '{}' has shape {:?}, expected [batch, 1, height, width]",
+                output.name, shape
+            ))),
+        }
+    }
+
+    /// 检查是否为文本识别模型，且输出的类别数与字典一致。`num_classes` 为包含空白的类别数，
+    /// 见 [`CtcDecoder::num_classes`](crate::CtcDecoder::num_classes)
+    ///
+    /// Check that this is a text recognition model whose number of output classes matches the
+    /// dictionary. `num_classes` includes the blank, see
+    /// [`CtcDecoder::num_classes`](crate::CtcDecoder::num_classes)
+    pub fn check_rec(&self, num_classes: usize, layout: OutputLayout) -> OcrResult<()> {
+        self.check_image_input("recognition")?;
+        let output = self.first_output("recognition")?;
+        let vocab_size = match (output.shape.as_slice(), layout) {
+            ([_, _, classes], OutputLayout::SequenceFirst) => *classes,
+            ([_, classes, _], OutputLayout::ClassesFirst) => *classes,
+            (shape, _) => {
+                return Err(OcrError::InputError(format!(
+                    "Recognition model output '{}' has shape {:?}, expected 3 dimensions",
+                    output.name, shape
+                )))
+            }
+        };
+        if vocab_size > 0 && vocab_size as usize != num_classes {
+            return Err(OcrError::InputError(format!(
+                "Dictionary defines {} classes including the blank, but the recognition model outputs {}",
+                num_classes, vocab_size
+            )));
+        }
+        Ok(())
+    }
+
+    /// 检查第一个输入是否为 [批大小, 3, 高, 宽] 的图像
+    fn check_image_input(&self, kind: &str) -> OcrResult<()> {
+        let input = self.inputs.first().ok_or_else(|| {
+            OcrError::InputError(format!("The {} model has no input tensor", kind))
+        })?;
+        match input.shape.as_slice() {
+            [_, channels, _, _] if *channels <= 0 || *channels == 3 => Ok(()),
+            shape => Err(OcrError::InputError(format!(
+                "The {} model input '{}' has shape {:?}, expected [batch, 3, height, width]",
+                kind, input.name, shape
+            ))),
+        }
+    }
+
+    fn first_output(&self, kind: &str) -> OcrResult<&TensorInfo> {
+        self.outputs
+            .first()
+            .ok_or_else(|| OcrError::InputError(format!("The {} model has no output tensor", kind)))
+    }
+}
+
+impl fmt::Display for ModelInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "inputs:")?;
+        for input in &self.inputs {
+            writeln!(f, "  {}", input)?;
+        }
+        writeln!(f, "outputs:")?;
+        for output in &self.outputs {
+            writeln!(f, "  {}", output)?;
+        }
+        Ok(())
+    }
+}
+
+/// 获取会话第一个输入和第一个输出张量的名称
+pub(crate) fn first_tensor_names(
+    interpreter: &Interpreter,
+    session: &Session,
+) -> OcrResult<(String, String)> {
+    let input = interpreter
+        .inputs(session)
+        .iter()
+        .next()
+        .map(|x| x.name().to_string())
+        .ok_or_else(|| OcrError::InputError("The model has no input tensor".to_string()))?;
+    let output = interpreter
+        .outputs(session)
+        .iter()
+        .next()
+        .map(|x| x.name().to_string())
+        .ok_or_else(|| OcrError::InputError("The model has no output tensor".to_string()))?;
+    Ok((input, output))
+}
+
+/// 收集张量列表的名称、形状和数据类型
+fn tensor_infos(list: &TensorList) -> Vec<TensorInfo> {
+    list.iter()
+        .map(|info| {
+            let tensor = info.raw_tensor();
+            let data_type = if tensor.is_type_of::<f32>() {
+                TensorDataType::F32
+            } else if tensor.is_type_of::<f64>() {
+                TensorDataType::F64
+            } else if tensor.is_type_of::<i8>() {
+                TensorDataType::I8
+            } else if tensor.is_type_of::<i16>() {
+                TensorDataType::I16
+            } else if tensor.is_type_of::<i32>() {
+                TensorDataType::I32
+            } else if tensor.is_type_of::<i64>() {
+                TensorDataType::I64
+            } else if tensor.is_type_of::<u8>() {
+                TensorDataType::U8
+            } else if tensor.is_type_of::<u16>() {
+                TensorDataType::U16
+            } else {
+                TensorDataType::Unknown
+            };
+            TensorInfo {
+                name: info.name().to_string(),
+                shape: tensor.shape().to_vec(),
+                data_type,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tensor(shape: &[i32]) -> TensorInfo {
+        TensorInfo {
+            name: "x".to_string(),
+            shape: shape.to_vec(),
+            data_type: TensorDataType::F32,
+        }
+    }
+
+    #[test]
+    fn test_checks() {
+        let rec = ModelInfo {
+            inputs: vec![tensor(&[-1, 3, 48, -1])],
+            outputs: vec![tensor(&[1, 160, 6625])],
+        };
+        assert!(rec.check_rec(6625, OutputLayout::SequenceFirst).is_ok());
+        assert!(rec.check_rec(18385, OutputLayout::SequenceFirst).is_err());
+        assert!(rec.check_det().is_err());
+
+        let det = ModelInfo {
+            inputs: vec![tensor(&[-1, 3, -1, -1])],
+            outputs: vec![tensor(&[1, 1, 640, 640])],
+        };
+        assert!(det.check_det().is_ok());
+        assert!(det.check_rec(6625, OutputLayout::SequenceFirst).is_err());
+    }
+}
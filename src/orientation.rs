@@ -0,0 +1,240 @@
+use image::{imageops::FilterType, DynamicImage, GenericImageView};
+use mnn::Interpreter;
+use ndarray::{Array, ArrayBase, Dim, OwnedRepr};
+use std::path::Path;
+
+use crate::cls::{to_probabilities, ClsResult};
+use crate::error::{OcrError, OcrResult};
+use crate::model_info::first_tensor_names;
+use crate::runtime::RuntimeOptions;
+
+/// 文档方向分类模型，判断整页图像的旋转角度（0°/90°/180°/270°）
+///
+/// Document orientation classifier that predicts the rotation of a whole page (0°/90°/180°/270°)
+pub struct DocOri {
+    interpreter: Interpreter,
+    session: Option<mnn::Session>,
+    runtime: RuntimeOptions,
+    // 缓存张量名称以避免重复查找
+    input_tensor_name: Option<String>,
+    output_tensor_name: Option<String>,
+}
+
+impl DocOri {
+    /// 模型输入边长
+    /// Model input side length
+    pub const INPUT_SIZE: u32 = 224;
+
+    /// 裁剪前短边缩放到的尺寸
+    /// Size the shorter side is resized to before cropping
+    const RESIZE_SHORT: u32 = 256;
+
+    /// 各类别对应的角度
+    /// Angle of each class
+    const LABELS: [u32; 4] = [0, 90, 180, 270];
+
+    /// 创建新的文档方向分类器实例
+    ///
+    /// Create a new document orientation classifier instance
+    pub fn new(interpreter: Interpreter) -> Self {
+        Self {
+            interpreter,
+            session: None,
+            runtime: RuntimeOptions::default(),
+            input_tensor_name: None,
+            output_tensor_name: None,
+        }
+    }
+
+    /// 从模型文件创建文档方向分类器
+    ///
+    /// Create a document orientation classifier from a model file
+    pub fn from_file(model_path: impl AsRef<Path>) -> OcrResult<Self> {
+        let interpreter = Interpreter::from_file(model_path)?;
+        Ok(Self::new(interpreter))
+    }
+
+    /// 从内存字节创建文档方向分类器
+    ///
+    /// Create a document orientation classifier from model bytes in memory
+    pub fn from_bytes(model_bytes: impl AsRef<[u8]>) -> OcrResult<Self> {
+        let interpreter = Interpreter::from_bytes(model_bytes)?;
+        Ok(Self::new(interpreter))
+    }
+
+    /// 设置推理运行时（线程数、精度、后端等）
+    ///
+    /// Set the inference runtime (threads, precision, backend, ...)
+    pub fn with_runtime(mut self, runtime: RuntimeOptions) -> Self {
+        self.runtime = runtime;
+        self.session = None;
+        self
+    }
+
+    /// 预测页面的旋转角度
+    ///
+    /// Predict the rotation angle of the page
+    pub fn predict(&mut self, img: &DynamicImage) -> OcrResult<ClsResult> {
+        let input = Self::preprocess(img)?;
+        let output = self.run_model(&input)?;
+        let probs = to_probabilities(&output);
+
+        let (index, score) = probs
+            .iter()
+            .copied()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .ok_or_else(|| OcrError::OutputError("Empty classifier output".to_string()))?;
+
+        let angle = *Self::LABELS.get(index).ok_or_else(|| {
+            OcrError::OutputError(format!(
+                "Unexpected classifier output size: {}",
+                probs.len()
+            ))
+        })?;
+
+        Ok(ClsResult { angle, score })
+    }
+
+    /// 短边缩放到 256 后中心裁剪 224×224，按 ImageNet 均值方差归一化
+    fn preprocess(img: &DynamicImage) -> OcrResult<ArrayBase<OwnedRepr<f32>, Dim<[usize; 4]>>> {
+        let (w, h) = img.dimensions();
+        if w == 0 || h == 0 {
+            return Err(OcrError::InputError("Empty image".to_string()));
+        }
+
+        let scale = Self::RESIZE_SHORT as f32 / w.min(h) as f32;
+        let resized_w = ((w as f32 * scale).round() as u32).max(Self::INPUT_SIZE);
+        let resized_h = ((h as f32 * scale).round() as u32).max(Self::INPUT_SIZE);
+        let resized = img
+            .resize_exact(resized_w, resized_h, FilterType::Triangle)
+            .crop_imm(
+                (resized_w - Self::INPUT_SIZE) / 2,
+                (resized_h - Self::INPUT_SIZE) / 2,
+                Self::INPUT_SIZE,
+                Self::INPUT_SIZE,
+            )
+            .to_rgb8();
+
+        let size = Self::INPUT_SIZE as usize;
+        let mut input = Array::zeros((1, 3, size, size));
+
+        const MEAN: [f32; 3] = [0.485, 0.456, 0.406];
+        const STD: [f32; 3] = [0.229, 0.224, 0.225];
+
+        for (x, y, pixel) in resized.enumerate_pixels() {
+            let (x, y) = (x as usize, y as usize);
+            for c in 0..3 {
+                input[[0, c, y, x]] = (pixel.0[c] as f32 / 255.0 - MEAN[c]) / STD[c];
+            }
+        }
+
+        Ok(input)
+    }
+
+    fn run_model(
+        &mut self,
+        input: &ArrayBase<OwnedRepr<f32>, Dim<[usize; 4]>>,
+    ) -> OcrResult<Vec<f32>> {
+        if self.session.is_none() {
+            let session = self
+                .interpreter
+                .create_session(self.runtime.schedule_config())?;
+            self.session = Some(session);
+        }
+
+        // 输入尺寸固定，只需在首次推理时调整张量大小
+        if self.input_tensor_name.is_none() || self.output_tensor_name.is_none() {
+            let session = self.session.as_mut().unwrap();
+            let (input_name, output_name) = first_tensor_names(&self.interpreter, session)?;
+
+            let shape = input.shape();
+            let mut input_tensor = unsafe {
+                self.interpreter
+                    .input_unresized::<f32>(session, &input_name)?
+            };
+            self.interpreter.resize_tensor(
+                &mut input_tensor,
+                [
+                    shape[0] as i32,
+                    shape[1] as i32,
+                    shape[2] as i32,
+                    shape[3] as i32,
+                ],
+            );
+            drop(input_tensor);
+            self.interpreter.resize_session(session);
+
+            self.input_tensor_name = Some(input_name);
+            self.output_tensor_name = Some(output_name);
+        }
+
+        let input_tensor_name = self.input_tensor_name.as_ref().unwrap();
+        let output_tensor_name = self.output_tensor_name.as_ref().unwrap();
+        let session = self.session.as_mut().unwrap();
+
+        let mut input_tensor = self.interpreter.input::<f32>(session, input_tensor_name)?;
+        let mut host_tensor = input_tensor.create_host_tensor_from_device(false);
+        let host_data_mut = host_tensor.host_mut();
+        for (i, val) in input.iter().enumerate() {
+            host_data_mut[i] = *val;
+        }
+        input_tensor.copy_from_host_tensor(&host_tensor)?;
+
+        self.interpreter.run_session(session)?;
+
+        let output = self
+            .interpreter
+            .output::<f32>(session, output_tensor_name)?;
+        output.wait(mnn::ffi::MapType::MAP_TENSOR_READ, true);
+        let output_host_tensor = output.create_host_tensor_from_device(true);
+
+        Ok(output_host_tensor.host().to_vec())
+    }
+}
+
+impl Drop for DocOri {
+    fn drop(&mut self) {
+        if let Some(session) = self.session.take() {
+            drop(session);
+        }
+    }
+}
+
+/// 将按顺时针旋转了 `angle` 度的图像转回正向
+///
+/// Turn an image that is rotated clockwise by `angle` degrees back upright
+pub fn rotate_upright(img: &DynamicImage, angle: u32) -> DynamicImage {
+    match angle % 360 {
+        90 => img.rotate270(),
+        180 => img.rotate180(),
+        270 => img.rotate90(),
+        _ => img.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn test_rotate_upright() {
+        // 左上角标记的横向图像，顺时针旋转90°后标记位于右上角
+        let mut upright = RgbImage::new(4, 2);
+        upright.put_pixel(0, 0, Rgb([255, 0, 0]));
+        let upright = DynamicImage::ImageRgb8(upright);
+
+        for angle in [0, 90, 180, 270] {
+            let rotated = match angle {
+                90 => upright.rotate90(),
+                180 => upright.rotate180(),
+                270 => upright.rotate270(),
+                _ => upright.clone(),
+            };
+            let restored = rotate_upright(&rotated, angle);
+            assert_eq!(restored.dimensions(), (4, 2));
+            assert_eq!(restored.to_rgb8().get_pixel(0, 0).0, [255, 0, 0]);
+        }
+    }
+}
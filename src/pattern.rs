@@ -0,0 +1,506 @@
+use crate::error::{OcrError, OcrResult};
+
+/// 字符类中的一项
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ClassItem {
+    Range(char, char),
+    Digit(bool),
+    Word(bool),
+    Space(bool),
+}
+
+impl ClassItem {
+    fn matches(&self, ch: char) -> bool {
+        match *self {
+            Self::Range(start, end) => (start..=end).contains(&ch),
+            Self::Digit(negated) => ch.is_ascii_digit() != negated,
+            Self::Word(negated) => (ch.is_alphanumeric() || ch == '_') != negated,
+            Self::Space(negated) => ch.is_whitespace() != negated,
+        }
+    }
+}
+
+/// 单个字符的匹配条件
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Matcher {
+    Any,
+    Class {
+        negated: bool,
+        items: Vec<ClassItem>,
+    },
+}
+
+impl Matcher {
+    fn literal(ch: char) -> Self {
+        Self::item(ClassItem::Range(ch, ch))
+    }
+
+    fn item(item: ClassItem) -> Self {
+        Self::Class {
+            negated: false,
+            items: vec![item],
+        }
+    }
+
+    fn matches(&self, ch: char) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Class { negated, items } => items.iter().any(|x| x.matches(ch)) != *negated,
+        }
+    }
+}
+
+/// 模式的语法树
+#[derive(Debug, Clone)]
+enum Node {
+    Empty,
+    Atom(Matcher),
+    Concat(Vec<Node>),
+    Alternate(Vec<Node>),
+    Repeat(Box<Node>, usize, Option<usize>),
+}
+
+/// 非确定有限自动机的状态
+#[derive(Debug, Clone)]
+enum State {
+    Match(Matcher, usize),
+    Split(Vec<usize>),
+    Accept,
+}
+
+/// 文本模式：正则表达式或格式掩码，用于约束识别结果的格式
+///
+/// Text pattern, either a regular expression or a format mask, used to constrain the format of
+/// recognition results
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    source: String,
+    states: Vec<State>,
+    start: usize,
+}
+
+/// 模式匹配的中间状态，即读入若干字符后可能处于的自动机状态集合
+///
+/// Intermediate matching state: the set of automaton states reachable after reading some characters
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PatternState(Vec<usize>);
+
+impl Pattern {
+    /// 单个量词允许的最大重复次数
+    const MAX_REPEAT: usize = 256;
+
+    /// 正则表达式编译后允许的最大状态数，防止嵌套量词使自动机过大
+    const MAX_STATES: usize = 1 << 16;
+
+    /// 解析正则表达式，整个文本须完整匹配。支持字面字符、`.`、字符类 `[...]`、`\d` `\w` `\s`
+    /// 及其大写取反形式、分组 `(...)`、分支 `|` 以及量词 `*` `+` `?` `{n}` `{n,}` `{n,m}`
+    ///
+    /// Parse a regular expression that must match the whole text. Supports literal characters,
+    /// `.`, character classes `[...]`, `\d` `\w` `\s` and their negated upper-case forms, groups
+    /// `(...)`, alternation `|` and the quantifiers `*` `+` `?` `{n}` `{n,}` `{n,m}`
+    pub fn regex(pattern: &str) -> OcrResult<Self> {
+        let mut parser = Parser {
+            chars: pattern.chars().collect(),
+            pos: 0,
+        };
+        let node = parser.parse_alternate()?;
+        if parser.pos < parser.chars.len() {
+            return Err(parser.error("unmatched ')'"));
+        }
+        Self::compile(pattern, &node, Self::MAX_STATES)
+    }
+
+    /// 解析格式掩码：`0` 表示数字，`A` 表示字母，`*` 表示字母或数字，`?` 表示任意字符，
+    /// `\` 转义下一个字符，其他字符按字面匹配，例如 `AA-0000-A`
+    ///
+    /// Parse a format mask: `0` is a digit, `A` a letter, `*` a letter or digit, `?` any
+    /// character, `\` escapes the next character and everything else matches literally,
+    /// e.g. `AA-0000-A`
+    pub fn mask(mask: &str) -> Self {
+        let mut nodes = Vec::new();
+        let mut chars = mask.chars();
+        while let Some(ch) = chars.next() {
+            let matcher = match ch {
+                '0' => Matcher::item(ClassItem::Digit(false)),
+                'A' => Matcher::Class {
+                    negated: false,
+                    items: vec![ClassItem::Range('A', 'Z'), ClassItem::Range('a', 'z')],
+                },
+                '*' => Matcher::Class {
+                    negated: false,
+                    items: vec![
+                        ClassItem::Range('0', '9'),
+                        ClassItem::Range('A', 'Z'),
+                        ClassItem::Range('a', 'z'),
+                    ],
+                },
+                '?' => Matcher::Any,
+                '\\' => match chars.next() {
+                    Some(escaped) => Matcher::literal(escaped),
+                    None => Matcher::literal('\\'),
+                },
+                _ => Matcher::literal(ch),
+            };
+            nodes.push(Node::Atom(matcher));
+        }
+        // 掩码每个字符只对应一个状态，不限制状态数
+        Self::compile(mask, &Node::Concat(nodes), usize::MAX)
+            .expect("compiling a mask without a state limit cannot fail")
+    }
+
+    /// 模式的原始文本
+    ///
+    /// The source text of the pattern
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// 文本是否完整匹配该模式
+    ///
+    /// Whether the whole text matches the pattern
+    pub fn matches(&self, text: &str) -> bool {
+        let mut state = self.start_state();
+        for ch in text.chars() {
+            match self.step(&state, ch) {
+                Some(next) => state = next,
+                None => return false,
+            }
+        }
+        self.is_accepting(&state)
+    }
+
+    /// 初始状态
+    ///
+    /// The initial state
+    pub fn start_state(&self) -> PatternState {
+        self.closure(&[self.start])
+    }
+
+    /// 在给定状态下读入一个字符，无法匹配时返回 `None`
+    ///
+    /// Read one character in the given state, returning `None` when it cannot match
+    pub fn step(&self, state: &PatternState, ch: char) -> Option<PatternState> {
+        let next: Vec<usize> = state
+            .0
+            .iter()
+            .filter_map(|&index| match &self.states[index] {
+                State::Match(matcher, next) if matcher.matches(ch) => Some(*next),
+                _ => None,
+            })
+            .collect();
+
+        if next.is_empty() {
+            None
+        } else {
+            Some(self.closure(&next))
+        }
+    }
+
+    /// 给定状态是否已完整匹配
+    ///
+    /// Whether the given state is a complete match
+    pub fn is_accepting(&self, state: &PatternState) -> bool {
+        state
+            .0
+            .iter()
+            .any(|&index| matches!(self.states[index], State::Accept))
+    }
+
+    /// 沿空转移展开状态集合，只保留匹配和接受状态
+    fn closure(&self, indices: &[usize]) -> PatternState {
+        let mut visited = vec![false; self.states.len()];
+        let mut stack = indices.to_vec();
+        let mut result = Vec::new();
+
+        while let Some(index) = stack.pop() {
+            if std::mem::replace(&mut visited[index], true) {
+                continue;
+            }
+            match &self.states[index] {
+                State::Split(targets) => stack.extend(targets.iter().copied()),
+                _ => result.push(index),
+            }
+        }
+
+        result.sort_unstable();
+        PatternState(result)
+    }
+
+    fn compile(source: &str, node: &Node, max_states: usize) -> OcrResult<Self> {
+        let mut states = vec![State::Accept];
+        let start = Self::compile_node(&mut states, node, 0, max_states)?;
+        Ok(Self {
+            source: source.to_string(),
+            states,
+            start,
+        })
+    }
+
+    /// 编译节点，使其匹配后转到 `out`，返回入口状态；状态数超过 `max_states` 时报错
+    fn compile_node(
+        states: &mut Vec<State>,
+        node: &Node,
+        out: usize,
+        max_states: usize,
+    ) -> OcrResult<usize> {
+        let push = |states: &mut Vec<State>, state| {
+            if states.len() >= max_states {
+                return Err(OcrError::InputError(format!(
+                    "Pattern is too complex: it needs more than {} states",
+                    max_states
+                )));
+            }
+            states.push(state);
+            Ok(states.len() - 1)
+        };
+
+        match node {
+            Node::Empty => Ok(out),
+            Node::Atom(matcher) => push(states, State::Match(matcher.clone(), out)),
+            Node::Concat(nodes) => nodes.iter().rev().try_fold(out, |next, node| {
+                Self::compile_node(states, node, next, max_states)
+            }),
+            Node::Alternate(nodes) => {
+                let entries = nodes
+                    .iter()
+                    .map(|node| Self::compile_node(states, node, out, max_states))
+                    .collect::<OcrResult<_>>()?;
+                push(states, State::Split(entries))
+            }
+            Node::Repeat(node, min, max) => {
+                let mut entry = match max {
+                    None => {
+                        // 先占位，再让循环体回到自身
+                        let lp = push(states, State::Split(Vec::new()))?;
+                        let body = Self::compile_node(states, node, lp, max_states)?;
+                        states[lp] = State::Split(vec![body, out]);
+                        lp
+                    }
+                    Some(max) => (*min..*max).try_fold(out, |next, _| {
+                        let body = Self::compile_node(states, node, next, max_states)?;
+                        push(states, State::Split(vec![body, out]))
+                    })?,
+                };
+                for _ in 0..*min {
+                    entry = Self::compile_node(states, node, entry, max_states)?;
+                }
+                Ok(entry)
+            }
+        }
+    }
+}
+
+/// 正则表达式解析器
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn error(&self, message: &str) -> OcrError {
+        OcrError::InputError(format!(
+            "Invalid pattern at position {}: {}",
+            self.pos, message
+        ))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.peek();
+        self.pos += 1;
+        ch
+    }
+
+    fn parse_alternate(&mut self) -> OcrResult<Node> {
+        let mut branches = vec![self.parse_concat()?];
+        while self.peek() == Some('|') {
+            self.pos += 1;
+            branches.push(self.parse_concat()?);
+        }
+        Ok(if branches.len() == 1 {
+            branches.pop().unwrap()
+        } else {
+            Node::Alternate(branches)
+        })
+    }
+
+    fn parse_concat(&mut self) -> OcrResult<Node> {
+        let mut nodes = Vec::new();
+        while let Some(ch) = self.peek() {
+            if ch == '|' || ch == ')' {
+                break;
+            }
+            let atom = self.parse_atom()?;
+            nodes.push(self.parse_quantifier(atom)?);
+        }
+        Ok(Node::Concat(nodes))
+    }
+
+    fn parse_atom(&mut self) -> OcrResult<Node> {
+        let ch = self.next().ok_or_else(|| self.error("unexpected end"))?;
+        let node = match ch {
+            '(' => {
+                if self.chars[self.pos..].starts_with(&['?', ':']) {
+                    self.pos += 2;
+                }
+                let node = self.parse_alternate()?;
+                if self.next() != Some(')') {
+                    return Err(self.error("missing ')'"));
+                }
+                node
+            }
+            // 模式总是完整匹配，忽略首尾锚点
+            '^' | '$' => Node::Empty,
+            '.' => Node::Atom(Matcher::Any),
+            '[' => Node::Atom(self.parse_class()?),
+            '\\' => Node::Atom(Matcher::item(self.parse_escape()?)),
+            '*' | '+' | '?' | '{' => return Err(self.error("nothing to repeat")),
+            _ => Node::Atom(Matcher::literal(ch)),
+        };
+        Ok(node)
+    }
+
+    fn parse_escape(&mut self) -> OcrResult<ClassItem> {
+        let ch = self.next().ok_or_else(|| self.error("unexpected end"))?;
+        let item = match ch {
+            'd' => ClassItem::Digit(false),
+            'D' => ClassItem::Digit(true),
+            'w' => ClassItem::Word(false),
+            'W' => ClassItem::Word(true),
+            's' => ClassItem::Space(false),
+            'S' => ClassItem::Space(true),
+            't' => ClassItem::Range('\t', '\t'),
+            _ => ClassItem::Range(ch, ch),
+        };
+        Ok(item)
+    }
+
+    fn parse_class(&mut self) -> OcrResult<Matcher> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.pos += 1;
+        }
+
+        let mut items = Vec::new();
+        let mut first = true;
+        loop {
+            let ch = self.next().ok_or_else(|| self.error("missing ']'"))?;
+            if ch == ']' && !first {
+                break;
+            }
+            first = false;
+
+            let item = if ch == '\\' {
+                self.parse_escape()?
+            } else {
+                ClassItem::Range(ch, ch)
+            };
+
+            // 范围 a-z
+            match item {
+                ClassItem::Range(start, _)
+                    if self.peek() == Some('-')
+                        && self.chars.get(self.pos + 1).is_some_and(|&x| x != ']') =>
+                {
+                    self.pos += 1;
+                    let end = match self.next() {
+                        Some('\\') => match self.parse_escape()? {
+                            ClassItem::Range(end, _) => end,
+                            _ => return Err(self.error("invalid class range")),
+                        },
+                        Some(end) => end,
+                        None => return Err(self.error("missing ']'")),
+                    };
+                    if end < start {
+                        return Err(self.error("invalid class range"));
+                    }
+                    items.push(ClassItem::Range(start, end));
+                }
+                item => items.push(item),
+            }
+        }
+
+        Ok(Matcher::Class { negated, items })
+    }
+
+    fn parse_quantifier(&mut self, atom: Node) -> OcrResult<Node> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => {
+                let start = self.pos;
+                self.pos += 1;
+                let min = self.parse_number();
+                let max = if self.peek() == Some(',') {
+                    self.pos += 1;
+                    self.parse_number()
+                } else {
+                    min
+                };
+                match (min, self.peek()) {
+                    (Some(min), Some('}')) => (min, max),
+                    _ => {
+                        // 不是合法的量词，按字面字符处理
+                        self.pos = start;
+                        return Ok(atom);
+                    }
+                }
+            }
+            _ => return Ok(atom),
+        };
+        self.pos += 1;
+
+        if max.is_some_and(|max| max < min) || min.max(max.unwrap_or(0)) > Pattern::MAX_REPEAT {
+            return Err(self.error("invalid repetition count"));
+        }
+        Ok(Node::Repeat(Box::new(atom), min, max))
+    }
+
+    fn parse_number(&mut self) -> Option<usize> {
+        let start = self.pos;
+        while self.peek().is_some_and(|ch| ch.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regex() {
+        let date = Pattern::regex(r"^\d{4}-(0[1-9]|1[0-2])-\d{2}$").unwrap();
+        assert!(date.matches("2024-03-15"));
+        assert!(!date.matches("2024-13-15"));
+        assert!(!date.matches("2024-03-1"));
+
+        let invoice = Pattern::regex("INV[-_]?[0-9A-F]+").unwrap();
+        assert!(invoice.matches("INV-00AF"));
+        assert!(invoice.matches("INV12"));
+        assert!(!invoice.matches("INV-"));
+
+        assert!(Pattern::regex("a{3,1}").is_err());
+        assert!(Pattern::regex("(ab").is_err());
+        assert!(Pattern::regex("*a").is_err());
+        // 嵌套量词展开后状态过多
+        assert!(Pattern::regex("((a{256}){256}){256}").is_err());
+    }
+
+    #[test]
+    fn test_mask() {
+        let plate = Pattern::mask("AA-0000-A");
+        assert!(plate.matches("AB-1234-C"));
+        assert!(!plate.matches("A8-1234-C"));
+        assert!(!plate.matches("AB-1234"));
+    }
+}
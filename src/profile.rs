@@ -0,0 +1,218 @@
+use std::str::FromStr;
+
+use crate::error::OcrError;
+
+/// 输入图像的逐通道归一化参数，像素值先缩放到 0~1，再减均值除以标准差
+///
+/// Per-channel input normalization; pixel values are scaled to 0~1, then the mean is
+/// subtracted and the result divided by the standard deviation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Normalization {
+    /// RGB 各通道的均值
+    /// Mean of each RGB channel
+    pub mean: [f32; 3],
+    /// RGB 各通道的标准差
+    /// Standard deviation of each RGB channel
+    pub std: [f32; 3],
+}
+
+impl Normalization {
+    /// ImageNet 统计量，PaddleOCR 检测模型使用
+    /// ImageNet statistics, used by PaddleOCR detection models
+    pub const IMAGENET: Self = Self {
+        mean: [0.485, 0.456, 0.406],
+        std: [0.229, 0.224, 0.225],
+    };
+
+    /// 均值和标准差均为 0.5，即映射到 -1~1，PaddleOCR 识别模型使用
+    /// Mean and standard deviation of 0.5, i.e. mapping to -1~1, used by PaddleOCR recognition
+    /// models
+    pub const HALF: Self = Self {
+        mean: [0.5; 3],
+        std: [0.5; 3],
+    };
+
+    /// 归一化单个通道的像素值
+    ///
+    /// Normalize the pixel value of a single channel
+    #[inline]
+    pub fn apply(&self, channel: usize, value: u8) -> f32 {
+        (value as f32 / 255.0 - self.mean[channel]) / self.std[channel]
+    }
+}
+
+/// 识别模型输出张量的维度顺序
+///
+/// Dimension order of the recognition model's output tensor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputLayout {
+    /// [批大小, 序列长度, 类别数]，PaddleOCR 导出模型的默认布局
+    /// [batch, sequence, classes], the default layout of exported PaddleOCR models
+    #[default]
+    SequenceFirst,
+    /// [批大小, 类别数, 序列长度]
+    /// [batch, classes, sequence]
+    ClassesFirst,
+}
+
+/// 模型的预处理和输出约定，不同 PaddleOCR 版本的模型需要使用对应的配置
+///
+/// Preprocessing and output conventions of a model pair; models from different PaddleOCR
+/// releases need the matching profile
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelProfile {
+    /// 检测输入的归一化参数
+    /// Normalization of the detection input
+    pub det_normalization: Normalization,
+    /// 检测输入的宽高会补齐到该值的整数倍
+    /// Width and height of the detection input are padded to a multiple of this value
+    pub det_pad_multiple: u32,
+    /// 识别输入的归一化参数
+    /// Normalization of the recognition input
+    pub rec_normalization: Normalization,
+    /// 识别输入的高度
+    /// Height of the recognition input
+    pub rec_input_height: u32,
+    /// 识别输入的最大宽度，更宽的文本行会被横向压缩；为 `None` 时不限制
+    /// Maximum width of the recognition input; wider lines are squeezed horizontally.
+    /// `None` means no limit
+    pub rec_max_width: Option<u32>,
+    /// 识别输出的维度顺序
+    /// Dimension order of the recognition output
+    pub rec_output_layout: OutputLayout,
+}
+
+impl ModelProfile {
+    /// PP-OCRv2，识别输入高度为 32
+    /// PP-OCRv2, with a recognition input height of 32
+    pub const PP_OCRV2: Self = Self {
+        det_normalization: Normalization::IMAGENET,
+        det_pad_multiple: 32,
+        rec_normalization: Normalization::HALF,
+        rec_input_height: 32,
+        rec_max_width: None,
+        rec_output_layout: OutputLayout::SequenceFirst,
+    };
+
+    /// PP-OCRv3，识别输入高度为 48
+    /// PP-OCRv3, with a recognition input height of 48
+    pub const PP_OCRV3: Self = Self {
+        rec_input_height: 48,
+        ..Self::PP_OCRV2
+    };
+
+    /// PP-OCRv4 轻量模型
+    /// PP-OCRv4 mobile models
+    pub const PP_OCRV4_MOBILE: Self = Self::PP_OCRV3;
+
+    /// PP-OCRv4 服务端模型
+    /// PP-OCRv4 server models
+    pub const PP_OCRV4_SERVER: Self = Self::PP_OCRV3;
+
+    /// PP-OCRv5 轻量模型，即内置模型使用的配置
+    /// PP-OCRv5 mobile models, the profile of the bundled models
+    pub const PP_OCRV5_MOBILE: Self = Self::PP_OCRV3;
+
+    /// PP-OCRv5 服务端模型
+    /// PP-OCRv5 server models
+    pub const PP_OCRV5_SERVER: Self = Self::PP_OCRV3;
+
+    /// 设置检测输入的归一化参数
+    ///
+    /// Set the normalization of the detection input
+    pub fn with_det_normalization(mut self, det_normalization: Normalization) -> Self {
+        self.det_normalization = det_normalization;
+        self
+    }
+
+    /// 设置检测输入补齐的倍数
+    ///
+    /// Set the multiple the detection input is padded to
+    pub fn with_det_pad_multiple(mut self, det_pad_multiple: u32) -> Self {
+        self.det_pad_multiple = det_pad_multiple.max(1);
+        self
+    }
+
+    /// 设置识别输入的归一化参数
+    ///
+    /// Set the normalization of the recognition input
+    pub fn with_rec_normalization(mut self, rec_normalization: Normalization) -> Self {
+        self.rec_normalization = rec_normalization;
+        self
+    }
+
+    /// 设置识别输入的高度
+    ///
+    /// Set the height of the recognition input
+    pub fn with_rec_input_height(mut self, rec_input_height: u32) -> Self {
+        self.rec_input_height = rec_input_height.max(1);
+        self
+    }
+
+    /// 设置识别输入的最大宽度
+    ///
+    /// Set the maximum width of the recognition input
+    pub fn with_rec_max_width(mut self, rec_max_width: u32) -> Self {
+        self.rec_max_width = Some(rec_max_width.max(1));
+        self
+    }
+
+    /// 设置识别输出的维度顺序
+    ///
+    /// Set the dimension order of the recognition output
+    pub fn with_rec_output_layout(mut self, rec_output_layout: OutputLayout) -> Self {
+        self.rec_output_layout = rec_output_layout;
+        self
+    }
+
+    /// 将长度补齐到检测输入要求的倍数
+    pub(crate) fn det_pad_length(&self, length: u32) -> u32 {
+        length.div_ceil(self.det_pad_multiple) * self.det_pad_multiple
+    }
+}
+
+impl Default for ModelProfile {
+    fn default() -> Self {
+        Self::PP_OCRV5_MOBILE
+    }
+}
+
+impl FromStr for ModelProfile {
+    type Err = OcrError;
+
+    /// 解析预设名称：v2、v3、v4-mobile、v4-server、v5-mobile、v5-server
+    ///
+    /// Parse a preset name: v2, v3, v4-mobile, v4-server, v5-mobile, v5-server
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "v2" | "v2-mobile" | "v2-server" => Ok(Self::PP_OCRV2),
+            "v3" | "v3-mobile" => Ok(Self::PP_OCRV3),
+            "v4" | "v4-mobile" => Ok(Self::PP_OCRV4_MOBILE),
+            "v4-server" => Ok(Self::PP_OCRV4_SERVER),
+            "v5" | "v5-mobile" => Ok(Self::PP_OCRV5_MOBILE),
+            "v5-server" => Ok(Self::PP_OCRV5_SERVER),
+            _ => Err(OcrError::InputError(format!(
+                "Unknown model profile: {}",
+                s
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile() {
+        let profile: ModelProfile = "v2".parse().unwrap();
+        assert_eq!(profile.rec_input_height, 32);
+        assert_eq!(ModelProfile::default().rec_input_height, 48);
+        assert!("v9".parse::<ModelProfile>().is_err());
+
+        let profile = ModelProfile::PP_OCRV5_SERVER.with_det_pad_multiple(64);
+        assert_eq!(profile.det_pad_length(64), 64);
+        assert_eq!(profile.det_pad_length(65), 128);
+        assert_eq!(Normalization::HALF.apply(0, 255), 1.0);
+    }
+}
@@ -1,11 +1,56 @@
 #[cfg(feature = "fast_image_resize")]
 use fast_image_resize::Resizer;
 use image::{DynamicImage, GenericImageView};
-use mnn::{BackendConfig, ForwardType, Interpreter, PowerMode, PrecisionMode, ScheduleConfig};
-use ndarray::{Array, ArrayBase, Dim, OwnedRepr};
-use std::{borrow::Cow, path::Path};
+use mnn::Interpreter;
+use ndarray::{s, Array, Array2, ArrayBase, Dim, OwnedRepr};
+use std::{borrow::Cow, path::Path, sync::Arc};
 
-use crate::error::OcrResult;
+use crate::charset::CharFilter;
+use crate::ctc::{CtcDecoder, CtcMode, CtcToken};
+use crate::error::{OcrError, OcrResult};
+use crate::geometry;
+use crate::lm::LanguageModel;
+use crate::model_info::first_tensor_names;
+use crate::pattern::Pattern;
+use crate::profile::{ModelProfile, OutputLayout};
+use crate::result::{CharSpan, LineRecognition, RecognizedChar, ScoreAggregation};
+use crate::runtime::RuntimeOptions;
+
+/// 单次识别调用的选项，设置后替代识别器自身的对应设置
+///
+/// Options for a single recognition call; when set they take the place of the recognizer's own
+/// settings
+#[derive(Debug, Clone, Default)]
+pub struct RecognizeOptions {
+    /// 字符过滤规则
+    /// Character filter
+    pub char_filter: Option<CharFilter>,
+    /// 约束识别结果的模式
+    /// Pattern constraining the recognition result
+    pub pattern: Option<Pattern>,
+    /// 模式匹配结果的最低平均置信度，低于该值视为没有匹配
+    /// Minimum mean confidence of a pattern match; lower-scoring matches count as no match
+    pub pattern_min_score: f32,
+}
+
+impl RecognizeOptions {
+    /// 设置字符过滤规则
+    ///
+    /// Set the character filter
+    pub fn with_char_filter(mut self, char_filter: CharFilter) -> Self {
+        self.char_filter = Some(char_filter);
+        self
+    }
+
+    /// 设置约束识别结果的模式及其最低平均置信度
+    ///
+    /// Set the pattern constraining the recognition result and its minimum mean confidence
+    pub fn with_pattern(mut self, pattern: Pattern, min_score: f32) -> Self {
+        self.pattern = Some(pattern);
+        self.pattern_min_score = min_score;
+        self
+    }
+}
 
 /// 文本识别模型
 ///
@@ -13,60 +58,95 @@ use crate::error::OcrResult;
 pub struct Rec {
     interpreter: Interpreter,
     session: Option<mnn::Session>,
-    keys: Vec<char>,
-    min_score: f32,
-    punct_min_score: f32,
+    decoder: CtcDecoder,
+    rotate_vertical: bool,
+    batch_size: usize,
+    width_buckets: Vec<u32>,
+    pattern: Option<Pattern>,
+    pattern_min_score: f32,
+    score_aggregation: ScoreAggregation,
+    profile: ModelProfile,
+    runtime: RuntimeOptions,
+    // 缓存张量名称以避免重复查找
+    input_tensor_name: Option<String>,
+    output_tensor_name: Option<String>,
+    // 缓存最后的输入形状，避免不必要的resize操作
+    last_input_shape: Option<[i32; 4]>,
     #[cfg(feature = "fast_resize")]
     resizer: fast_image_resize::Resizer,
 }
 
 impl Rec {
-    const MIN_SCORE_DEFAULT: f32 = 0.6;
-    const PUNCT_MIN_SCORE_DEFAULT: f32 = 0.1;
+    /// 默认的批量识别大小
+    /// Default batch size for batched recognition
+    pub const BATCH_SIZE_DEFAULT: usize = 6;
 
-    const PUNCTUATIONS: [char; 49] = [
-        ',', '.', '!', '?', ';', ':', '"', '\'', '(', ')', '[', ']', '{', '}', '-', '_', '/', '\\',
-        '|', '@', '#', '$', '%', '&', '*', '+', '=', '~', '，', '。', '！', '？', '；', '：', '、',
-        '「', '」', '『', '』', '（', '）', '【', '】', '《', '》', '—', '…', '·', '～',
-    ];
+    /// 默认的输入宽度分档，最小档与 PaddleOCR 的默认输入宽度 320 一致
+    /// Default input width buckets; the smallest matches PaddleOCR's default input width of 320
+    pub const WIDTH_BUCKETS_DEFAULT: [u32; 5] = [320, 640, 960, 1280, 1920];
 
-    /// 创建新的文本识别器实例
+    /// 创建新的文本识别器实例，`keys` 为完整的类别表，索引 0 为空白占位
     ///
-    /// Create a new text recognizer instance
-    pub fn new(interpreter: Interpreter, keys: Vec<char>) -> Self {
+    /// Create a new text recognizer instance; `keys` is the full class table whose index 0 is
+    /// a blank placeholder
+    pub fn new<S: Into<String>>(
+        interpreter: Interpreter,
+        keys: impl IntoIterator<Item = S>,
+    ) -> Self {
+        Self::with_parts(interpreter, CtcDecoder::from_classes(keys))
+    }
+
+    fn with_parts(interpreter: Interpreter, decoder: CtcDecoder) -> Self {
         Self {
             interpreter,
             session: None,
-            keys,
-            min_score: Self::MIN_SCORE_DEFAULT,
-            punct_min_score: Self::PUNCT_MIN_SCORE_DEFAULT,
+            decoder,
+            rotate_vertical: true,
+            batch_size: Self::BATCH_SIZE_DEFAULT,
+            width_buckets: Self::WIDTH_BUCKETS_DEFAULT.to_vec(),
+            pattern: None,
+            pattern_min_score: 0.0,
+            score_aggregation: ScoreAggregation::default(),
+            profile: ModelProfile::default(),
+            runtime: RuntimeOptions::default(),
+            input_tensor_name: None,
+            output_tensor_name: None,
+            last_input_shape: None,
             #[cfg(feature = "fast_resize")]
             resizer: fast_image_resize::Resizer::new(),
         }
     }
 
-    /// 从模型文件和字符集文件创建文本识别器
+    /// 从模型文件和字符集文件创建文本识别器，字符集文件每行一个条目，条目数须与模型输出的
+    /// 类别数一致
     ///
-    /// Create a text recognizer from model file and character set file
+    /// Create a text recognizer from model file and character set file; the character set file
+    /// holds one entry per line, and the entry count must match the model's output classes
     pub fn from_file(model_path: impl AsRef<Path>, keys_path: impl AsRef<Path>) -> OcrResult<Self> {
+        Self::from_file_with_options(
+            model_path,
+            keys_path,
+            ModelProfile::default(),
+            RuntimeOptions::default(),
+        )
+    }
+
+    /// 按给定的模型配置和推理运行时从模型文件和字符集文件创建文本识别器，类别数按配置的输出
+    /// 布局检查
+    ///
+    /// Create a text recognizer for the given model profile and inference runtime from model
+    /// file and character set file; the class count is checked against the profile's output
+    /// layout
+    pub fn from_file_with_options(
+        model_path: impl AsRef<Path>,
+        keys_path: impl AsRef<Path>,
+        profile: ModelProfile,
+        runtime: RuntimeOptions,
+    ) -> OcrResult<Self> {
         let interpreter = Interpreter::from_file(model_path)?;
         let keys_content = std::fs::read_to_string(keys_path)?;
 
-        let keys = " "
-            .chars()
-            .chain(keys_content.chars().filter(|x| *x != '\n' && *x != '\r'))
-            .chain(" ".chars())
-            .collect();
-
-        Ok(Self {
-            interpreter,
-            session: None,
-            keys,
-            min_score: Self::MIN_SCORE_DEFAULT,
-            punct_min_score: Self::PUNCT_MIN_SCORE_DEFAULT,
-            #[cfg(feature = "fast_resize")]
-            resizer: fast_image_resize::Resizer::new(),
-        })
+        Self::load(interpreter, &keys_content, profile, runtime)
     }
 
     /// 从模型字节创建文本识别器，需要提供字符集文件路径
@@ -79,21 +159,12 @@ impl Rec {
         let interpreter = Interpreter::from_bytes(model_bytes)?;
         let keys_content = std::fs::read_to_string(keys_path)?;
 
-        let keys = " "
-            .chars()
-            .chain(keys_content.chars().filter(|x| *x != '\n' && *x != '\r'))
-            .chain(" ".chars())
-            .collect();
-
-        Ok(Self {
+        Self::load(
             interpreter,
-            session: None,
-            keys,
-            min_score: Self::MIN_SCORE_DEFAULT,
-            punct_min_score: Self::PUNCT_MIN_SCORE_DEFAULT,
-            #[cfg(feature = "fast_resize")]
-            resizer: fast_image_resize::Resizer::new(),
-        })
+            &keys_content,
+            ModelProfile::default(),
+            RuntimeOptions::default(),
+        )
     }
 
     /// 从模型字节和字符集字节创建文本识别器
@@ -102,60 +173,475 @@ impl Rec {
     pub fn from_bytes_with_keys(
         model_bytes: impl AsRef<[u8]>,
         keys_bytes: impl AsRef<[u8]>,
+    ) -> OcrResult<Self> {
+        Self::from_bytes_with_keys_and_options(
+            model_bytes,
+            keys_bytes,
+            ModelProfile::default(),
+            RuntimeOptions::default(),
+        )
+    }
+
+    /// 按给定的模型配置和推理运行时从模型字节和字符集字节创建文本识别器
+    ///
+    /// Create a text recognizer for the given model profile and inference runtime from model
+    /// bytes and character set bytes
+    pub fn from_bytes_with_keys_and_options(
+        model_bytes: impl AsRef<[u8]>,
+        keys_bytes: impl AsRef<[u8]>,
+        profile: ModelProfile,
+        runtime: RuntimeOptions,
     ) -> OcrResult<Self> {
         let interpreter = Interpreter::from_bytes(model_bytes)?;
         let keys_content = std::str::from_utf8(keys_bytes.as_ref()).map_err(|e| {
             crate::error::OcrError::IOError(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
         })?;
 
-        let keys = " "
-            .chars()
-            .chain(keys_content.chars().filter(|x| *x != '\n' && *x != '\r'))
-            .chain(" ".chars())
-            .collect();
+        Self::load(interpreter, keys_content, profile, runtime)
+    }
 
-        Ok(Self {
-            interpreter,
-            session: None,
-            keys,
-            min_score: Self::MIN_SCORE_DEFAULT,
-            punct_min_score: Self::PUNCT_MIN_SCORE_DEFAULT,
-            #[cfg(feature = "fast_resize")]
-            resizer: fast_image_resize::Resizer::new(),
-        })
+    /// 按模型配置和推理运行时创建识别器，并检查字典与模型输出是否一致；检查时创建的会话会被
+    /// 后续推理复用
+    fn load(
+        interpreter: Interpreter,
+        keys_content: &str,
+        profile: ModelProfile,
+        runtime: RuntimeOptions,
+    ) -> OcrResult<Self> {
+        Self::with_parts(interpreter, CtcDecoder::from_dict_str(keys_content))
+            .with_profile(profile)
+            .with_runtime(runtime)
+            .check_vocab()
     }
 
-    /// 设置常规字符的最小识别置信度阈值
+    /// 设置常规字符的最小识别置信度阈值：启发式解码丢弃低于阈值的字符，其他解码方式保留这些
+    /// 字符并将其标记为不确定
     ///
-    /// Set the minimum confidence threshold for regular characters
+    /// Set the minimum confidence threshold for regular characters: heuristic decoding drops
+    /// characters below it, while the other modes keep them and flag them as uncertain
     pub fn with_min_score(mut self, min_score: f32) -> Self {
-        self.min_score = min_score;
+        self.decoder = self.decoder.clone().with_min_score(min_score);
         self
     }
 
-    /// 设置标点符号的最小识别置信度阈值
+    /// 设置标点符号的最小识别置信度阈值，仅在启发式解码中生效
     ///
-    /// Set the minimum confidence threshold for punctuation characters
+    /// Set the minimum confidence threshold for punctuation characters; only used by heuristic
+    /// decoding
     pub fn with_punct_min_score(mut self, punct_min_score: f32) -> Self {
-        self.punct_min_score = punct_min_score;
+        self.decoder = self.decoder.clone().with_punct_min_score(punct_min_score);
+        self
+    }
+
+    /// 设置 CTC 解码方式，默认为与 PaddleOCR 一致的标准贪心解码
+    ///
+    /// Set the CTC decoding mode; defaults to standard greedy decoding matching PaddleOCR
+    pub fn with_ctc_mode(mut self, mode: CtcMode) -> Self {
+        self.decoder = self.decoder.clone().with_mode(mode);
         self
     }
 
-    #[inline]
-    fn is_punctuation(&self, ch: char) -> bool {
-        Self::PUNCTUATIONS.contains(&ch)
+    /// 设置束搜索的束宽
+    ///
+    /// Set the beam width of beam search
+    pub fn with_beam_width(mut self, beam_width: usize) -> Self {
+        self.decoder = self.decoder.clone().with_beam_width(beam_width);
+        self
+    }
+
+    /// 设置束搜索使用的词典或语言模型
+    ///
+    /// Set the lexicon or language model used by beam search
+    pub fn with_language_model(mut self, lm: Arc<dyn LanguageModel>) -> Self {
+        self.decoder = self.decoder.clone().with_language_model(lm);
+        self
+    }
+
+    /// 设置语言模型得分的权重
+    ///
+    /// Set the weight of the language model score
+    pub fn with_lm_weight(mut self, lm_weight: f32) -> Self {
+        self.decoder = self.decoder.clone().with_lm_weight(lm_weight);
+        self
+    }
+
+    /// 设置字符过滤规则，只输出允许的字符
+    ///
+    /// Set the character filter so that only allowed characters are emitted
+    pub fn with_char_filter(mut self, char_filter: CharFilter) -> Self {
+        self.decoder = self.decoder.clone().with_char_filter(char_filter);
+        self
+    }
+
+    /// 设置约束识别结果的模式：输出完整匹配模式的最可能文本，若没有平均置信度不低于
+    /// `min_score` 的匹配，则输出不受约束的识别结果并标记为未匹配
+    ///
+    /// Set the pattern constraining recognition results: the most probable text fully matching
+    /// the pattern is returned; when no match has a mean confidence of at least `min_score`, the
+    /// unconstrained result is returned and flagged as not matching
+    pub fn with_pattern(mut self, pattern: Pattern, min_score: f32) -> Self {
+        self.pattern = Some(pattern);
+        self.pattern_min_score = min_score;
+        self
+    }
+
+    /// 设置由字符置信度计算文本行和词置信度的方式，默认为算术平均
+    ///
+    /// Set how line and word confidences are computed from character confidences; defaults to
+    /// the arithmetic mean
+    pub fn with_score_aggregation(mut self, score_aggregation: ScoreAggregation) -> Self {
+        self.score_aggregation = score_aggregation;
+        self
+    }
+
+    /// 当前计算文本行和词置信度的方式
+    ///
+    /// The current way of computing line and word confidences
+    pub fn score_aggregation(&self) -> ScoreAggregation {
+        self.score_aggregation
+    }
+
+    /// 设置模型的预处理和输出配置，默认为 PP-OCRv5 轻量模型的配置。字典类别数在创建时按
+    /// 当时的输出布局检查，输出布局不同的模型请使用 `from_file_with_options` 等构造函数
+    ///
+    /// Set the preprocessing and output profile of the model; defaults to the PP-OCRv5 mobile
+    /// profile. The dictionary's class count is checked against the output layout at creation,
+    /// so load models with a different output layout through `from_file_with_options` and the
+    /// like
+    pub fn with_profile(mut self, profile: ModelProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// 获取当前的模型配置
+    ///
+    /// Get the current model profile
+    pub fn profile(&self) -> &ModelProfile {
+        &self.profile
+    }
+
+    /// 设置推理运行时（线程数、精度、后端等），默认使用低精度、高性能模式
+    ///
+    /// Set the inference runtime (threads, precision, backend, ...); defaults to low precision
+    /// in high power mode
+    pub fn with_runtime(mut self, runtime: RuntimeOptions) -> Self {
+        if runtime == self.runtime {
+            return self;
+        }
+        self.runtime = runtime;
+        // 已创建的会话按新设置重建
+        self.session = None;
+        self.last_input_shape = None;
+        self
+    }
+
+    /// 获取当前的推理运行时设置
+    ///
+    /// Get the current inference runtime settings
+    pub fn runtime(&self) -> &RuntimeOptions {
+        &self.runtime
+    }
+
+    /// 替换 CTC 解码器，用于自定义空白索引或空格字符等
+    ///
+    /// Replace the CTC decoder, e.g. to customize the blank index or the space character
+    pub fn with_decoder(mut self, decoder: CtcDecoder) -> Self {
+        self.decoder = decoder;
+        self
+    }
+
+    /// 当前使用的 CTC 解码器
+    ///
+    /// The CTC decoder in use
+    pub fn decoder(&self) -> &CtcDecoder {
+        &self.decoder
+    }
+
+    /// 设置是否将竖排文本行（高宽比不小于1.5）逆时针旋转90度后再识别，默认开启
+    ///
+    /// Set whether vertical text lines (height/width ratio of at least 1.5) are rotated 90 degrees
+    /// counter-clockwise before recognition; enabled by default
+    pub fn with_rotate_vertical(mut self, rotate_vertical: bool) -> Self {
+        self.rotate_vertical = rotate_vertical;
+        self
+    }
+
+    /// 是否将竖排文本行旋转后再识别
+    ///
+    /// Whether vertical text lines are rotated before recognition
+    pub fn rotate_vertical(&self) -> bool {
+        self.rotate_vertical
+    }
+
+    /// 设置批量识别时每次推理的最大图像数量
+    ///
+    /// Set the maximum number of images per inference call in batched recognition
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// 设置输入宽度分档：输入会在右侧补零到不小于其宽度的最小分档，只有分档变化时才需要
+    /// 调整会话大小。超过最大分档的输入保持原宽度，传入空列表则关闭分档
+    ///
+    /// Set the input width buckets: inputs are zero-padded on the right to the smallest bucket
+    /// not narrower than them, so the session is only resized when the bucket changes. Inputs
+    /// wider than the largest bucket keep their width; an empty list disables bucketing
+    pub fn with_width_buckets(mut self, width_buckets: Vec<u32>) -> Self {
+        self.width_buckets = Self::sorted_buckets(width_buckets);
+        self
+    }
+
+    /// 排序并去重宽度分档
+    fn sorted_buckets(mut width_buckets: Vec<u32>) -> Vec<u32> {
+        width_buckets.sort_unstable();
+        width_buckets.dedup();
+        width_buckets
+    }
+
+    /// 计算给定宽度对应的输入宽度分档
+    ///
+    /// Compute the input width bucket for the given width
+    fn bucket_width(&self, width: usize) -> usize {
+        Self::find_bucket(&self.width_buckets, width)
+    }
+
+    /// 在已排序的分档中查找不小于给定宽度的最小分档，没有时保持原宽度
+    fn find_bucket(width_buckets: &[u32], width: usize) -> usize {
+        width_buckets
+            .iter()
+            .map(|&bucket| bucket as usize)
+            .find(|&bucket| bucket >= width)
+            .unwrap_or(width)
     }
 
     /// 识别图像中的文本，返回字符及其置信度
     ///
     /// Recognize text in the image, returning characters and their confidence scores
-    pub fn predict_char_score(&mut self, img: &DynamicImage) -> OcrResult<Vec<(char, f32)>> {
-        #[cfg(not(feature = "fast_resize"))]
-        let input = Self::preprocess(img)?;
-        #[cfg(feature = "fast_resize")]
-        let input = Self::preprocess(img, &mut self.resizer)?;
-        let output = self.run_model(&input)?;
-        Ok(output)
+    pub fn predict_char_score(&mut self, img: &DynamicImage) -> OcrResult<Vec<(String, f32)>> {
+        let probs = self.predict_logits(img)?;
+        let (sequence_length, vocab_size) = probs.dim();
+        Ok(self.decode(
+            probs.as_slice().unwrap_or_default(),
+            sequence_length,
+            vocab_size,
+        ))
+    }
+
+    /// 运行识别模型并返回文本行图像的输出概率矩阵 [序列长度, 字符集大小]，不做解码。
+    /// 对应宽度分档补零区域的时间步已被去除
+    ///
+    /// Run the recognition model and return the output probability matrix [sequence, vocab] of
+    /// the text line image without decoding. Time steps covering the zero padding added for the
+    /// width bucket are removed
+    pub fn predict_logits(&mut self, img: &DynamicImage) -> OcrResult<Array2<f32>> {
+        self.infer_line(img).map(|(probs, ..)| probs)
+    }
+
+    /// 覆盖图像内容（不含右侧补零）的时间步数
+    fn content_steps(sequence_length: usize, content_width: usize, padded_width: usize) -> usize {
+        if padded_width <= content_width {
+            return sequence_length;
+        }
+        (sequence_length * content_width)
+            .div_ceil(padded_width)
+            .min(sequence_length)
+    }
+
+    /// 截取单个样本 [序列长度, 字符集大小] 中覆盖图像内容的时间步，返回截取后的数据和时间步数
+    fn content_sample(
+        sample: &[f32],
+        sequence_length: usize,
+        vocab_size: usize,
+        content_width: usize,
+        padded_width: usize,
+    ) -> (&[f32], usize) {
+        let steps = Self::content_steps(sequence_length, content_width, padded_width);
+        (&sample[..steps * vocab_size], steps)
+    }
+
+    /// 识别图像中的文本，返回每个字符及其在文本行图像中的大致水平范围
+    ///
+    /// Recognize text in the image, returning every character with its approximate horizontal
+    /// extent in the text line image
+    pub fn predict_spans(&mut self, img: &DynamicImage) -> OcrResult<Vec<CharSpan>> {
+        self.recognize_line(img).map(|line| line.spans)
+    }
+
+    /// 识别单张文本行图像的完整结果
+    fn recognize_line(&mut self, img: &DynamicImage) -> OcrResult<LineRecognition> {
+        let (probs, sequence_length, content_width, padded_width) = self.infer_line(img)?;
+        let (steps, vocab_size) = probs.dim();
+        let (tokens, matches_pattern) =
+            self.decode_line(probs.as_slice().unwrap_or_default(), steps, vocab_size);
+        Ok(self.line_recognition(
+            self.char_spans(tokens, sequence_length, content_width, padded_width),
+            matches_pattern,
+        ))
+    }
+
+    /// 预处理并运行单张图像，返回去除补零区域时间步后的输出概率矩阵、模型输出的序列长度、
+    /// 内容宽度和补零后的输入宽度
+    fn infer_line(&mut self, img: &DynamicImage) -> OcrResult<(Array2<f32>, usize, usize, usize)> {
+        let img = self.rotate_if_vertical(img);
+        let img = self.fit_height(&img);
+        let input = self.preprocess_line(&img)?;
+
+        // 右侧补零到宽度分档，减少会话大小调整
+        let width = input.shape()[3];
+        let bucket_width = self.bucket_width(width);
+        let input = Self::pad_width(input, bucket_width);
+
+        let (output, [_, sequence_length, vocab_size]) = self.run_model(&input)?;
+        let (sample, steps) =
+            Self::content_sample(&output, sequence_length, vocab_size, width, bucket_width);
+        let probs = Array2::from_shape_vec((steps, vocab_size), sample.to_vec())?;
+        Ok((probs, sequence_length, width, bucket_width))
+    }
+
+    /// 在右侧补零到给定宽度
+    fn pad_width(
+        input: ArrayBase<OwnedRepr<f32>, Dim<[usize; 4]>>,
+        width: usize,
+    ) -> ArrayBase<OwnedRepr<f32>, Dim<[usize; 4]>> {
+        let (batch, channels, height, input_width) = input.dim();
+        if input_width >= width {
+            return input;
+        }
+        let mut padded = Array::zeros((batch, channels, height, width));
+        padded
+            .slice_mut(s![.., .., .., ..input_width])
+            .assign(&input);
+        padded
+    }
+
+    /// 识别图像中的文本，每个字符附带其所在时间步上概率最高的 `top_k` 个候选字符
+    ///
+    /// Recognize text in the image; every character carries the `top_k` most probable
+    /// alternative characters at its time step
+    pub fn predict_detailed(
+        &mut self,
+        img: &DynamicImage,
+        top_k: usize,
+    ) -> OcrResult<Vec<RecognizedChar>> {
+        let probs = self.predict_logits(img)?;
+        let (sequence_length, vocab_size) = probs.dim();
+        let tokens = self.decoder.decode(
+            probs.as_slice().unwrap_or_default(),
+            sequence_length,
+            vocab_size,
+        );
+
+        Ok(tokens
+            .into_iter()
+            .map(|token| RecognizedChar {
+                text: token.text,
+                score: token.score,
+                uncertain: self.decoder.is_uncertain(token.score),
+                timestep: token.timestep,
+                alternatives: self.decoder.alternatives(
+                    probs.row(token.timestep).as_slice().unwrap_or_default(),
+                    top_k,
+                    token.index,
+                ),
+            })
+            .collect())
+    }
+
+    /// 批量识别多张文本行图像，返回每张图像的字符及其置信度，顺序与输入一致
+    ///
+    /// 图像按宽高比排序后每 `batch_size` 张一组，统一缩放到模型输入高度并右侧补零到组内最大宽度
+    /// 所在的宽度分档，每组只运行一次推理。
+    ///
+    /// Recognize many text line images in batches, returning the characters and confidence scores
+    /// of each image in input order
+    ///
+    /// Images are sorted by aspect ratio and grouped by `batch_size`; each group is scaled to the
+    /// model input height, zero-padded on the right to the width bucket of the widest image, and
+    /// run in one inference call.
+    pub fn predict_char_score_batch(
+        &mut self,
+        imgs: &[DynamicImage],
+    ) -> OcrResult<Vec<Vec<(String, f32)>>> {
+        let results = self.predict_spans_batch(imgs)?;
+        Ok(results
+            .into_iter()
+            .map(|spans| spans.into_iter().map(|x| (x.text, x.score)).collect())
+            .collect())
+    }
+
+    /// 批量识别多张文本行图像，返回每个字符及其在文本行图像中的大致水平范围，顺序与输入一致
+    ///
+    /// Recognize many text line images in batches, returning every character with its
+    /// approximate horizontal extent in the text line image, in input order
+    pub fn predict_spans_batch(&mut self, imgs: &[DynamicImage]) -> OcrResult<Vec<Vec<CharSpan>>> {
+        let results = self.recognize_lines(imgs)?;
+        Ok(results.into_iter().map(|line| line.spans).collect())
+    }
+
+    /// 批量识别多张文本行图像的完整结果，顺序与输入一致
+    fn recognize_lines(&mut self, imgs: &[DynamicImage]) -> OcrResult<Vec<LineRecognition>> {
+        let lines: Vec<Cow<DynamicImage>> = imgs
+            .iter()
+            .map(|img| match self.rotate_if_vertical(img) {
+                Cow::Borrowed(img) => self.fit_height(img),
+                Cow::Owned(img) => Cow::Owned(self.fit_height(&img).into_owned()),
+            })
+            .collect();
+
+        // 宽高比相近的图像放在同一批，减少补零
+        let aspect_ratio = |img: &DynamicImage| img.width() as f32 / img.height().max(1) as f32;
+        let mut order: Vec<usize> = (0..lines.len()).collect();
+        order.sort_by(|&a, &b| aspect_ratio(&lines[a]).total_cmp(&aspect_ratio(&lines[b])));
+
+        let mut results = vec![LineRecognition::default(); lines.len()];
+        for chunk in order.chunks(self.batch_size) {
+            let inputs = chunk
+                .iter()
+                .map(|&i| self.preprocess_line(&lines[i]))
+                .collect::<OcrResult<Vec<_>>>()?;
+
+            let max_width = inputs.iter().map(|x| x.shape()[3]).max().unwrap_or(1);
+            let max_width = self.bucket_width(max_width);
+            let height = self.profile.rec_input_height as usize;
+            let mut batch = Array::zeros((chunk.len(), 3, height, max_width));
+            for (b, input) in inputs.iter().enumerate() {
+                let (height, width) = (input.shape()[2], input.shape()[3]);
+                batch
+                    .slice_mut(s![b, .., ..height, ..width])
+                    .assign(&input.slice(s![0, .., .., ..]));
+            }
+
+            let (output, [_, sequence_length, vocab_size]) = self.run_model(&batch)?;
+            let sample_size = sequence_length * vocab_size;
+            for (b, &i) in chunk.iter().enumerate() {
+                let content_width = inputs[b].shape()[3];
+                let (sample, steps) = Self::content_sample(
+                    &output[b * sample_size..(b + 1) * sample_size],
+                    sequence_length,
+                    vocab_size,
+                    content_width,
+                    max_width,
+                );
+                let (tokens, matches_pattern) = self.decode_line(sample, steps, vocab_size);
+                let spans = self.char_spans(tokens, sequence_length, content_width, max_width);
+                results[i] = self.line_recognition(spans, matches_pattern);
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// 批量识别多张文本行图像，返回每张图像的文本和平均置信度，顺序与输入一致
+    ///
+    /// Recognize many text line images in batches, returning the text and mean confidence of
+    /// each image in input order
+    pub fn predict_batch(&mut self, imgs: &[DynamicImage]) -> OcrResult<Vec<(String, f32)>> {
+        let results = self.predict_char_score_batch(imgs)?;
+        Ok(results
+            .into_iter()
+            .map(|x| self.text_with_confidence(x))
+            .collect())
     }
 
     /// 识别图像中的文本，返回字符串
@@ -171,146 +657,440 @@ impl Rec {
     /// Recognize text in the image, returning a string and confidence score
     pub fn predict_with_confidence(&mut self, img: &DynamicImage) -> OcrResult<(String, f32)> {
         let char_scores = self.predict_char_score(img)?;
+        Ok(self.text_with_confidence(char_scores))
+    }
+
+    /// 按单次调用的选项识别图像中的文本
+    ///
+    /// Recognize text in the image with per-call options
+    pub fn predict_with_options(
+        &mut self,
+        img: &DynamicImage,
+        options: &RecognizeOptions,
+    ) -> OcrResult<LineRecognition> {
+        self.with_call_options(options, |rec| rec.recognize_line(img))
+    }
+
+    /// 按单次调用的选项批量识别多张文本行图像，顺序与输入一致
+    ///
+    /// Recognize many text line images in batches with per-call options, in input order
+    pub fn predict_batch_with_options(
+        &mut self,
+        imgs: &[DynamicImage],
+        options: &RecognizeOptions,
+    ) -> OcrResult<Vec<LineRecognition>> {
+        self.with_call_options(options, |rec| rec.recognize_lines(imgs))
+    }
+
+    /// 识别完整匹配 `pattern` 的最可能文本，返回文本和平均置信度；没有平均置信度不低于
+    /// `min_score` 的匹配时返回 `None`
+    ///
+    /// Recognize the most probable text fully matching `pattern`, returning the text and mean
+    /// confidence; returns `None` when no match has a mean confidence of at least `min_score`
+    pub fn predict_pattern(
+        &mut self,
+        img: &DynamicImage,
+        pattern: &Pattern,
+        min_score: f32,
+    ) -> OcrResult<Option<(String, f32)>> {
+        let options = RecognizeOptions::default().with_pattern(pattern.clone(), min_score);
+        let line = self.predict_with_options(img, &options)?;
+        Ok((line.matches_pattern == Some(true)).then_some((line.text, line.score)))
+    }
+
+    /// 在调用期间临时应用单次调用的选项
+    fn with_call_options<R>(
+        &mut self,
+        options: &RecognizeOptions,
+        f: impl FnOnce(&mut Self) -> OcrResult<R>,
+    ) -> OcrResult<R> {
+        let previous_decoder = options.char_filter.as_ref().map(|char_filter| {
+            let decoder = self.decoder.clone().with_char_filter(char_filter.clone());
+            std::mem::replace(&mut self.decoder, decoder)
+        });
+        let previous_pattern = options.pattern.as_ref().map(|pattern| {
+            let previous = (
+                self.pattern.replace(pattern.clone()),
+                self.pattern_min_score,
+            );
+            self.pattern_min_score = options.pattern_min_score;
+            previous
+        });
+
+        let result = f(self);
+
+        if let Some(decoder) = previous_decoder {
+            self.decoder = decoder;
+        }
+        if let Some((pattern, min_score)) = previous_pattern {
+            self.pattern = pattern;
+            self.pattern_min_score = min_score;
+        }
+        result
+    }
+
+    /// 解码单个样本；设置了模式时优先返回匹配模式的结果，并报告是否匹配
+    fn decode_line(
+        &self,
+        output_data: &[f32],
+        sequence_length: usize,
+        vocab_size: usize,
+    ) -> (Vec<CtcToken>, Option<bool>) {
+        let Some(pattern) = &self.pattern else {
+            return (
+                self.decoder
+                    .decode(output_data, sequence_length, vocab_size),
+                None,
+            );
+        };
+
+        let matched = self
+            .decoder
+            .decode_pattern(output_data, sequence_length, vocab_size, pattern)
+            .filter(|tokens| {
+                let score =
+                    tokens.iter().map(|x| x.score).sum::<f32>() / tokens.len().max(1) as f32;
+                score >= self.pattern_min_score
+            });
+        match matched {
+            Some(tokens) => (tokens, Some(true)),
+            None => (
+                self.decoder
+                    .decode(output_data, sequence_length, vocab_size),
+                Some(false),
+            ),
+        }
+    }
+
+    fn line_recognition(
+        &self,
+        spans: Vec<CharSpan>,
+        matches_pattern: Option<bool>,
+    ) -> LineRecognition {
+        let (text, score) =
+            self.text_with_confidence(spans.iter().map(|x| (x.text.clone(), x.score)).collect());
+        LineRecognition {
+            text,
+            score,
+            spans,
+            matches_pattern,
+        }
+    }
+
+    /// 由字符所在的时间步估算其在文本行图像中的范围：每个时间步对应输入中等宽的一列，
+    /// 相邻字符以中心点的中点为界，单个字符的宽度不超过文本行高度
+    ///
+    /// Estimate the extent of each character in the text line image from its time step: every
+    /// time step covers an equally wide column of the input, neighbouring characters are split
+    /// halfway between their centers, and no character is wider than the line is tall
+    fn char_spans(
+        &self,
+        tokens: Vec<CtcToken>,
+        sequence_length: usize,
+        content_width: usize,
+        padded_width: usize,
+    ) -> Vec<CharSpan> {
+        Self::estimate_spans(
+            tokens,
+            sequence_length,
+            content_width,
+            padded_width,
+            self.profile.rec_input_height,
+            &self.decoder,
+        )
+    }
+
+    fn estimate_spans(
+        tokens: Vec<CtcToken>,
+        sequence_length: usize,
+        content_width: usize,
+        padded_width: usize,
+        line_height: u32,
+        decoder: &CtcDecoder,
+    ) -> Vec<CharSpan> {
+        let content_width = content_width.max(1) as f32;
+        let step = padded_width as f32 / sequence_length.max(1) as f32 / content_width;
+        let half_width = line_height as f32 / content_width / 2.0;
+
+        let centers: Vec<f32> = tokens
+            .iter()
+            .map(|token| (token.timestep as f32 + 0.5) * step)
+            .collect();
+
+        tokens
+            .into_iter()
+            .enumerate()
+            .map(|(i, token)| {
+                let center = centers[i];
+                let mut start = center - half_width;
+                let mut end = center + half_width;
+                if i > 0 {
+                    start = start.max((centers[i - 1] + center) / 2.0);
+                }
+                if let Some(&next) = centers.get(i + 1) {
+                    end = end.min((center + next) / 2.0);
+                }
+                CharSpan {
+                    text: token.text,
+                    score: token.score,
+                    uncertain: decoder.is_uncertain(token.score),
+                    start: start.clamp(0.0, 1.0),
+                    end: end.clamp(0.0, 1.0),
+                }
+            })
+            .collect()
+    }
 
-        if char_scores.is_empty() {
-            return Ok((String::new(), 0.0));
+    /// 将字符及置信度合并为字符串和文本行置信度
+    ///
+    /// Combine characters and their scores into a string and the line confidence
+    fn text_with_confidence(&self, char_scores: Vec<(String, f32)>) -> (String, f32) {
+        let score = self
+            .score_aggregation
+            .aggregate(char_scores.iter().map(|(_, score)| *score));
+        let text: String = char_scores.into_iter().map(|(text, _)| text).collect();
+        (text, score)
+    }
+
+    /// 竖排文本行缩放到固定高度会被压扁，先逆时针旋转90度为横排
+    ///
+    /// Vertical text lines would be squashed when scaled to a fixed height, so rotate them
+    /// 90 degrees counter-clockwise first
+    fn rotate_if_vertical<'a>(&self, img: &'a DynamicImage) -> Cow<'a, DynamicImage> {
+        let (w, h) = img.dimensions();
+        if self.rotate_vertical && geometry::is_vertical(w as f32, h as f32) {
+            Cow::Owned(img.rotate270())
+        } else {
+            Cow::Borrowed(img)
         }
+    }
 
-        // 计算平均置信度
-        let total_score: f32 = char_scores.iter().map(|(_, score)| *score).sum();
-        let avg_score = total_score / char_scores.len() as f32;
+    /// 将低于模型输入高度的图像放大到输入高度，使输入形状只随宽度变化
+    ///
+    /// Scale images shorter than the model input height up to it, so input shapes only vary in width
+    fn fit_height<'a>(&self, img: &'a DynamicImage) -> Cow<'a, DynamicImage> {
+        let height = self.profile.rec_input_height;
+        let (w, h) = img.dimensions();
+        if h >= height {
+            return Cow::Borrowed(img);
+        }
 
-        // 提取文本
-        let text: String = char_scores.into_iter().map(|(ch, _)| ch).collect();
+        let resized_w = ((w * height) as f32 / h.max(1) as f32).round().max(1.0) as u32;
+        Cow::Owned(img.resize_exact(resized_w, height, image::imageops::FilterType::CatmullRom))
+    }
 
-        Ok((text, avg_score))
+    /// 预处理单张文本行图像
+    ///
+    /// Preprocess a single text line image
+    fn preprocess_line(
+        &mut self,
+        img: &DynamicImage,
+    ) -> OcrResult<ArrayBase<OwnedRepr<f32>, Dim<[usize; 4]>>> {
+        #[cfg(not(feature = "fast_resize"))]
+        let input = Self::preprocess(img, &self.profile)?;
+        #[cfg(feature = "fast_resize")]
+        let input = Self::preprocess(img, &self.profile, &mut self.resizer)?;
+        Ok(input)
     }
 
     #[cfg(feature = "fast_resize")]
     fn preprocess(
         img: &DynamicImage,
+        profile: &ModelProfile,
         resizer: &mut Resizer,
     ) -> OcrResult<ArrayBase<OwnedRepr<f32>, Dim<[usize; 4]>>> {
         use fast_image_resize::{FilterType, ResizeAlg, ResizeOptions};
         let (w, h) = img.dimensions();
-        let img = if h <= 48 {
+        let (target_w, target_h) = Self::input_size(w, h, profile);
+        let img = if (target_w, target_h) == (w, h) {
             Cow::Borrowed(img)
         } else {
             let resize_option =
                 ResizeOptions::new().resize_alg(ResizeAlg::Convolution(FilterType::CatmullRom));
-            let mut dst_img = DynamicImage::new(w * 48 / h, 48, img.color());
+            let mut dst_img = DynamicImage::new(target_w, target_h, img.color());
             resizer.resize(img, &mut dst_img, &resize_option)?;
             Cow::Owned(dst_img)
         };
 
-        let (w, h) = img.dimensions();
-        let mut input = Array::zeros((1, 3, h as usize, w as usize));
-
-        const MEAN: f32 = 0.5;
-        const STD: f32 = 0.5;
-
-        for pixel in img.pixels() {
-            let x = pixel.0 as usize;
-            let y = pixel.1 as usize;
-            let [r, g, b, _] = pixel.2 .0;
-
-            input[[0, 0, y, x]] = (r as f32 / 255.0 - MEAN) / STD;
-            input[[0, 1, y, x]] = (g as f32 / 255.0 - MEAN) / STD;
-            input[[0, 2, y, x]] = (b as f32 / 255.0 - MEAN) / STD;
-        }
-
-        Ok(input)
+        Ok(Self::normalize(&img, profile))
     }
 
     #[cfg(not(feature = "fast_resize"))]
-    fn preprocess(img: &DynamicImage) -> OcrResult<ArrayBase<OwnedRepr<f32>, Dim<[usize; 4]>>> {
+    fn preprocess(
+        img: &DynamicImage,
+        profile: &ModelProfile,
+    ) -> OcrResult<ArrayBase<OwnedRepr<f32>, Dim<[usize; 4]>>> {
         let (w, h) = img.dimensions();
-        let img = if h <= 48 {
+        let (target_w, target_h) = Self::input_size(w, h, profile);
+        let img = if (target_w, target_h) == (w, h) {
             Cow::Borrowed(img)
         } else {
-            Cow::Owned(img.resize_exact(w * 48 / h, 48, image::imageops::FilterType::CatmullRom))
+            Cow::Owned(img.resize_exact(
+                target_w,
+                target_h,
+                image::imageops::FilterType::CatmullRom,
+            ))
+        };
+
+        Ok(Self::normalize(&img, profile))
+    }
+
+    /// 计算识别输入尺寸：高于输入高度的图像等比缩小，超过最大宽度的图像横向压缩
+    fn input_size(width: u32, height: u32, profile: &ModelProfile) -> (u32, u32) {
+        let input_height = profile.rec_input_height;
+        let (mut w, h) = if height <= input_height {
+            (width, height)
+        } else {
+            ((width * input_height / height).max(1), input_height)
         };
+        if let Some(max_width) = profile.rec_max_width {
+            w = w.min(max_width);
+        }
+        (w, h)
+    }
 
+    /// 按配置归一化图像像素
+    fn normalize(
+        img: &DynamicImage,
+        profile: &ModelProfile,
+    ) -> ArrayBase<OwnedRepr<f32>, Dim<[usize; 4]>> {
         let (w, h) = img.dimensions();
         let mut input = Array::zeros((1, 3, h as usize, w as usize));
-
-        const MEAN: f32 = 0.5;
-        const STD: f32 = 0.5;
+        let normalization = profile.rec_normalization;
 
         for pixel in img.pixels() {
             let x = pixel.0 as usize;
             let y = pixel.1 as usize;
             let [r, g, b, _] = pixel.2 .0;
 
-            input[[0, 0, y, x]] = (r as f32 / 255.0 - MEAN) / STD;
-            input[[0, 1, y, x]] = (g as f32 / 255.0 - MEAN) / STD;
-            input[[0, 2, y, x]] = (b as f32 / 255.0 - MEAN) / STD;
+            input[[0, 0, y, x]] = normalization.apply(0, r);
+            input[[0, 1, y, x]] = normalization.apply(1, g);
+            input[[0, 2, y, x]] = normalization.apply(2, b);
         }
 
-        Ok(input)
+        input
     }
 
-    fn run_model(
-        &mut self,
-        input: &ArrayBase<OwnedRepr<f32>, Dim<[usize; 4]>>,
-    ) -> OcrResult<Vec<(char, f32)>> {
-        if self.session.is_none() {
-            let mut config = ScheduleConfig::new();
-            config.set_type(ForwardType::Auto);
-
-            let mut backend_config = BackendConfig::new();
-            // 使用更低精度以提升性能
-            backend_config.set_precision_mode(PrecisionMode::Low);
-            backend_config.set_power_mode(PowerMode::High);
+    /// 检查字典的类别数与模型输出的类别数是否一致
+    fn check_vocab(mut self) -> OcrResult<Self> {
+        // 优先使用模型声明的输入尺寸，动态维度使用默认值
+        self.ensure_session()?;
+        let declared = {
+            let session = self.session.as_mut().unwrap();
+            let input_tensor_info = self.input_tensor_name.as_ref().unwrap();
+            let input_tensor = unsafe {
+                self.interpreter
+                    .input_unresized::<f32>(session, input_tensor_info)?
+            };
+            input_tensor.shape()
+        };
+        let dim = |index: usize, default: u32| match declared.get(index) {
+            Some(&value) if value > 0 => value,
+            _ => default as i32,
+        };
+        let shape = [
+            1,
+            3,
+            dim(2, self.profile.rec_input_height),
+            dim(3, Self::WIDTH_BUCKETS_DEFAULT[0]),
+        ];
+        self.prepare_session(shape)?;
 
-            config.set_backend_config(backend_config);
+        let vocab_size = {
+            let session = self.session.as_ref().unwrap();
+            let output_tensor_info = self.output_tensor_name.as_ref().unwrap();
+            let output = self
+                .interpreter
+                .output::<f32>(session, output_tensor_info)?;
+            let shape = output.shape().to_vec();
+            match (shape.as_slice(), self.profile.rec_output_layout) {
+                ([_, _, classes], OutputLayout::SequenceFirst) => *classes,
+                ([_, classes, _], OutputLayout::ClassesFirst) => *classes,
+                (shape, _) => {
+                    return Err(OcrError::InputError(format!(
+                        "Recognition model output has shape {:?}, expected 3 dimensions",
+                        shape
+                    )))
+                }
+            }
+        };
+        let num_classes = self.decoder.num_classes();
+        if vocab_size > 0 && vocab_size as usize != num_classes {
+            return Err(OcrError::InputError(format!(
+                "Dictionary defines {} classes including the blank, but the recognition model outputs {}",
+                num_classes, vocab_size
+            )));
+        }
+        Ok(self)
+    }
 
-            let session = self.interpreter.create_session(config)?;
+    /// 按需创建会话并缓存输入输出张量名称
+    fn ensure_session(&mut self) -> OcrResult<()> {
+        if self.session.is_none() {
+            let session = self
+                .interpreter
+                .create_session(self.runtime.schedule_config())?;
             self.session = Some(session);
         }
 
-        // 获取输入输出张量列表，然后取第一个
-        let (input_tensor_info, output_tensor_info) = {
+        // 获取或缓存输入输出张量名称
+        if self.input_tensor_name.is_none() || self.output_tensor_name.is_none() {
             let session = self.session.as_ref().unwrap();
-            let inputs = self.interpreter.inputs(session);
-            let outputs = self.interpreter.outputs(session);
+            let (input_name, output_name) = first_tensor_names(&self.interpreter, session)?;
+            self.input_tensor_name = Some(input_name);
+            self.output_tensor_name = Some(output_name);
+        }
 
-            // 获取第一个输入和输出张量的信息
-            let input_info = inputs.iter().next().unwrap();
-            let output_info = outputs.iter().next().unwrap();
+        Ok(())
+    }
 
-            (
-                input_info.name().to_string(),
-                output_info.name().to_string(),
-            )
-        };
+    /// 按需创建会话并将输入调整为给定形状
+    fn prepare_session(&mut self, new_shape: [i32; 4]) -> OcrResult<()> {
+        self.ensure_session()?;
 
-        let input_shape = input.shape();
-        {
+        // 只在形状变化时才重新调整张量大小
+        if self.last_input_shape != Some(new_shape) {
+            let input_tensor_info = self.input_tensor_name.as_ref().unwrap();
             let session = self.session.as_mut().unwrap();
             let mut input_tensor = unsafe {
                 self.interpreter
-                    .input_unresized::<f32>(session, &input_tensor_info)?
+                    .input_unresized::<f32>(session, input_tensor_info)?
             };
 
-            self.interpreter.resize_tensor(
-                &mut input_tensor,
-                [
-                    input_shape[0] as i32,
-                    input_shape[1] as i32,
-                    input_shape[2] as i32,
-                    input_shape[3] as i32,
-                ],
-            );
+            self.interpreter.resize_tensor(&mut input_tensor, new_shape);
 
             drop(input_tensor);
 
             self.interpreter.resize_session(session);
+
+            // 缓存当前形状
+            self.last_input_shape = Some(new_shape);
         }
 
+        Ok(())
+    }
+
+    /// 运行识别模型，返回输出数据及其形状 [批大小, 序列长度, 字符集大小]
+    ///
+    /// Run the recognition model, returning the output data and its shape [batch, sequence, vocab]
+    fn run_model(
+        &mut self,
+        input: &ArrayBase<OwnedRepr<f32>, Dim<[usize; 4]>>,
+    ) -> OcrResult<(Vec<f32>, [usize; 3])> {
+        let input_shape = input.shape();
+        self.prepare_session([
+            input_shape[0] as i32,
+            input_shape[1] as i32,
+            input_shape[2] as i32,
+            input_shape[3] as i32,
+        ])?;
+
+        let input_tensor_info = self.input_tensor_name.as_ref().unwrap();
+        let output_tensor_info = self.output_tensor_name.as_ref().unwrap();
+
         let (output_data, output_shape) = {
             let session = self.session.as_mut().unwrap();
-            let mut input_tensor = self.interpreter.input::<f32>(session, &input_tensor_info)?;
+            let mut input_tensor = self.interpreter.input::<f32>(session, input_tensor_info)?;
 
             if let Some(flat_data) = input.as_slice() {
                 let mut host_tensor = input_tensor.create_host_tensor_from_device(false);
@@ -330,74 +1110,69 @@ impl Rec {
 
             let output = self
                 .interpreter
-                .output::<f32>(session, &output_tensor_info)?;
+                .output::<f32>(session, output_tensor_info)?;
             output.wait(mnn::ffi::MapType::MAP_TENSOR_READ, true);
 
-            let shape = output.shape();
+            let shape = output.shape().to_vec();
             let output_host_tensor = output.create_host_tensor_from_device(true);
             (output_host_tensor.host().to_vec(), shape)
         };
 
-        let sequence_length = output_shape[1] as usize;
-        let vocab_size = output_shape[2] as usize;
-
-        let mut results = Vec::with_capacity(sequence_length);
-        let mut last_char: Option<char> = None;
-
-        for i in 0..sequence_length {
-            let mut max_idx = 0;
-            let mut max_score = 0.0f32;
-
-            for j in 0..vocab_size {
-                let offset = i * vocab_size + j;
-                if offset < output_data.len() && output_data[offset] > max_score {
-                    max_score = output_data[offset];
-                    max_idx = j;
+        let batch_size = input_shape[0];
+        let (sequence_length, vocab_size) =
+            match (output_shape.as_slice(), self.profile.rec_output_layout) {
+                ([_, sequence, classes], OutputLayout::SequenceFirst) => {
+                    (*sequence as usize, *classes as usize)
                 }
-            }
-
-            if max_idx > 0 && max_idx < self.keys.len() {
-                if let Some(&ch) = self.keys.get(max_idx) {
-                    let threshold = if self.is_punctuation(ch) {
-                        self.punct_min_score
-                    } else {
-                        self.min_score
-                    };
-
-                    if max_score > threshold {
-                        if last_char != Some(ch) || self.is_punctuation(ch) {
-                            results.push((ch, max_score));
-                        }
-                        last_char = Some(ch);
-                    } else {
-                        if self.is_punctuation(ch) && max_score > self.punct_min_score * 0.8 {
-                            results.push((ch, max_score));
-                        } else {
-                            last_char = None;
-                        }
-                    }
+                ([_, classes, sequence], OutputLayout::ClassesFirst) => {
+                    (*sequence as usize, *classes as usize)
                 }
-            } else {
-                last_char = None;
-            }
+                (shape, _) => {
+                    return Err(OcrError::OutputError(format!(
+                        "Unexpected recognition output shape: {:?}, expected 3 dimensions",
+                        shape
+                    )))
+                }
+            };
+        if output_data.len() < batch_size * sequence_length * vocab_size {
+            return Err(OcrError::OutputError(format!(
+                "Unexpected recognition output size: {} for shape [{}, {}, {}]",
+                output_data.len(),
+                batch_size,
+                sequence_length,
+                vocab_size
+            )));
         }
 
-        let mut final_results = Vec::with_capacity(results.len());
-        let mut i = 0;
-        while i < results.len() {
-            let (ch, score) = results[i];
-            final_results.push((ch, score));
-
-            if self.is_punctuation(ch) {
-                while i + 1 < results.len() && results[i + 1].0 == ch {
-                    i += 1;
-                }
+        // 统一转换为 [批大小, 序列长度, 类别数]
+        let output_data = match self.profile.rec_output_layout {
+            OutputLayout::SequenceFirst => output_data,
+            OutputLayout::ClassesFirst => {
+                let output = Array::from_shape_vec(
+                    (batch_size, vocab_size, sequence_length),
+                    output_data[..batch_size * vocab_size * sequence_length].to_vec(),
+                )?;
+                output.permuted_axes([0, 2, 1]).iter().copied().collect()
             }
+        };
 
-            i += 1;
-        }
+        Ok((output_data, [batch_size, sequence_length, vocab_size]))
+    }
 
-        Ok(final_results)
+    /// 解码单个样本的模型输出 [序列长度, 字符集大小]
+    ///
+    /// Decode the model output of a single sample [sequence, vocab]
+    fn decode(
+        &self,
+        output_data: &[f32],
+        sequence_length: usize,
+        vocab_size: usize,
+    ) -> Vec<(String, f32)> {
+        self.decode_line(output_data, sequence_length, vocab_size)
+            .0
+            .into_iter()
+            .map(|token| (token.text, token.score))
+            .collect()
     }
 }
 
@@ -408,3 +1183,126 @@ impl Drop for Rec {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::TextQuad;
+    use crate::result;
+
+    #[test]
+    fn test_content_steps() {
+        // 没有补零时保留全部时间步
+        assert_eq!(Rec::content_steps(40, 320, 320), 40);
+        // 补零区域对应的时间步被去除，部分覆盖内容的时间步保留
+        assert_eq!(Rec::content_steps(80, 320, 640), 40);
+        assert_eq!(Rec::content_steps(80, 321, 640), 41);
+        assert_eq!(Rec::content_steps(80, 1, 640), 1);
+    }
+
+    #[test]
+    fn test_default_buckets() {
+        let buckets = Rec::WIDTH_BUCKETS_DEFAULT;
+        assert_eq!(Rec::find_bucket(&buckets, 1), 320);
+        assert_eq!(Rec::find_bucket(&buckets, 320), 320);
+        assert_eq!(Rec::find_bucket(&buckets, 321), 640);
+        assert_eq!(Rec::find_bucket(&buckets, 1920), 1920);
+        // 超过最大分档时保持原宽度
+        assert_eq!(Rec::find_bucket(&buckets, 2500), 2500);
+    }
+
+    #[test]
+    fn test_custom_buckets() {
+        let buckets = Rec::sorted_buckets(vec![800, 200, 400, 200]);
+        assert_eq!(buckets, vec![200, 400, 800]);
+        assert_eq!(Rec::find_bucket(&buckets, 150), 200);
+        assert_eq!(Rec::find_bucket(&buckets, 401), 800);
+        assert_eq!(Rec::find_bucket(&buckets, 900), 900);
+
+        // 空列表关闭分档
+        assert_eq!(Rec::find_bucket(&Rec::sorted_buckets(Vec::new()), 123), 123);
+    }
+
+    #[test]
+    fn test_pad_width() {
+        let input = Array::from_elem((1, 3, 2, 3), 1.0);
+        let padded = Rec::pad_width(input.clone(), 5);
+        assert_eq!(padded.dim(), (1, 3, 2, 5));
+        assert!(padded.slice(s![.., .., .., ..3]).iter().all(|&x| x == 1.0));
+        assert!(padded.slice(s![.., .., .., 3..]).iter().all(|&x| x == 0.0));
+
+        // 不比输入窄的宽度不补零
+        assert_eq!(Rec::pad_width(input.clone(), 3), input);
+        assert_eq!(Rec::pad_width(input.clone(), 2), input);
+    }
+
+    #[test]
+    fn test_char_spans() {
+        // 类别：0 空白, 1 'a', 2 'b', 3 空格；前 4 个时间步覆盖 32 像素宽的内容，其余为补零
+        let decoder = CtcDecoder::new(["a", "b"]);
+        let path = [1, 2, 3, 1, 0, 0, 0, 0];
+        let mut probs = Array2::zeros((path.len(), 4));
+        for (t, &index) in path.iter().enumerate() {
+            probs[[t, index]] = 0.9;
+        }
+        let tokens = decoder.decode(probs.as_slice().unwrap(), path.len(), 4);
+        let spans = Rec::estimate_spans(tokens, path.len(), 32, 64, 8, &decoder);
+
+        let ranges: Vec<(&str, f32, f32)> = spans
+            .iter()
+            .map(|x| (x.text.as_str(), x.start, x.end))
+            .collect();
+        assert_eq!(
+            ranges,
+            [
+                ("a", 0.0, 0.25),
+                ("b", 0.25, 0.5),
+                (" ", 0.5, 0.75),
+                ("a", 0.75, 1.0)
+            ]
+        );
+
+        let quad = TextQuad::from_rect(&imageproc::rect::Rect::at(0, 0).of_size(32, 8));
+        let (_, words) = result::line_spans(&spans, &quad, ScoreAggregation::Mean);
+        let words: Vec<(&str, f32, f32)> = words
+            .iter()
+            .map(|x| (x.text.as_str(), x.quad.points[0].x, x.quad.points[1].x))
+            .collect();
+        assert_eq!(words, [("ab", 0.0, 16.0), ("a", 24.0, 32.0)]);
+    }
+
+    #[test]
+    fn test_single_and_batch_padding_agree() {
+        // 类别：0 空白, 1 'a', 2 'b', 3 空格；330 像素宽的内容单独识别时补零到 640，
+        // 批量识别时补零到 960，补零区域的输出为干扰字符
+        let decoder = CtcDecoder::new(["a", "b"]);
+        let output = |sequence_length: usize| {
+            let mut probs = vec![0.0; sequence_length * 4];
+            for t in 0..sequence_length {
+                let index = match t {
+                    10 => 1,
+                    40 => 2,
+                    82 => 1,
+                    t if t < 83 => 0,
+                    _ => 2,
+                };
+                probs[t * 4 + index] = 0.9;
+            }
+            probs
+        };
+        let recognize = |sequence_length: usize, padded_width: usize| {
+            let output = output(sequence_length);
+            let (sample, steps) =
+                Rec::content_sample(&output, sequence_length, 4, 330, padded_width);
+            let tokens = decoder.decode(sample, steps, 4);
+            Rec::estimate_spans(tokens, sequence_length, 330, padded_width, 48, &decoder)
+        };
+
+        let single = recognize(160, 640);
+        let batch = recognize(240, 960);
+        let text: String = single.iter().map(|x| x.text.as_str()).collect();
+        assert_eq!(text, "aba");
+        assert_eq!(single, batch);
+        assert!(single.iter().all(|x| x.end > x.start));
+    }
+}
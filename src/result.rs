@@ -0,0 +1,320 @@
+use std::str::FromStr;
+
+use imageproc::rect::Rect;
+
+use crate::error::OcrError;
+use crate::geometry::TextQuad;
+
+/// 由字符置信度计算文本行或词置信度的方式
+///
+/// How the confidence of a text line or word is computed from its character confidences
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScoreAggregation {
+    /// 算术平均
+    /// Arithmetic mean
+    #[default]
+    Mean,
+    /// 最小值，任一字符不确定时整体即不确定
+    /// Minimum; the whole is as uncertain as its least certain character
+    Min,
+    /// 几何平均，比算术平均更易受低置信度字符影响
+    /// Geometric mean, pulled down by low-confidence characters more than the arithmetic mean
+    GeometricMean,
+}
+
+impl ScoreAggregation {
+    /// 合并多个置信度，没有置信度时返回 0
+    ///
+    /// Combine several confidences, returning 0 when there are none
+    pub fn aggregate(self, scores: impl IntoIterator<Item = f32>) -> f32 {
+        let mut count = 0usize;
+        let mut acc = match self {
+            Self::Mean | Self::GeometricMean => 0.0,
+            Self::Min => f32::INFINITY,
+        };
+        for score in scores {
+            count += 1;
+            match self {
+                Self::Mean => acc += score,
+                Self::Min => acc = acc.min(score),
+                Self::GeometricMean => acc += score.max(f32::MIN_POSITIVE).ln(),
+            }
+        }
+
+        if count == 0 {
+            return 0.0;
+        }
+        match self {
+            Self::Mean => acc / count as f32,
+            Self::Min => acc,
+            Self::GeometricMean => (acc / count as f32).exp(),
+        }
+    }
+}
+
+impl FromStr for ScoreAggregation {
+    type Err = OcrError;
+
+    /// 解析 mean、min 或 geometric-mean
+    ///
+    /// Parse mean, min or geometric-mean
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mean" => Ok(Self::Mean),
+            "min" => Ok(Self::Min),
+            "geometric-mean" | "geomean" => Ok(Self::GeometricMean),
+            _ => Err(OcrError::InputError(format!(
+                "Unknown score aggregation: {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// 单个文本行的识别结果
+///
+/// Recognition result of a single text line
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLine {
+    /// 识别出的文本
+    /// Recognized text
+    pub text: String,
+    /// 识别置信度，由各字符置信度按识别器的 [`ScoreAggregation`] 合并（0~1）
+    /// Recognition confidence, combined from the character confidences using the recognizer's
+    /// [`ScoreAggregation`] (0~1)
+    pub score: f32,
+    /// 检测得分（0~1）
+    /// Detection score (0~1)
+    pub det_score: f32,
+    /// 轴对齐矩形框
+    /// Axis-aligned rectangle
+    pub rect: Rect,
+    /// 旋转四边形框
+    /// Rotated quadrilateral
+    pub quad: TextQuad,
+    /// 是否为竖排文本行；竖排文本行在识别前已旋转为横排，阅读顺序为从上到下
+    /// Whether this is a vertical text line; vertical lines are rotated to horizontal before
+    /// recognition and read top to bottom
+    pub vertical: bool,
+    /// 各字符的大致位置，由识别时间步估算
+    /// Approximate location of each character, estimated from recognition time steps
+    pub chars: Vec<TextSpan>,
+    /// 各词的大致位置：按空白分词，中日文字符各自成词
+    /// Approximate location of each word: words are split at whitespace, and every CJK
+    /// character forms a word of its own
+    pub words: Vec<TextSpan>,
+    /// 请求中设置了模式时，文本是否为模式匹配结果；未匹配时文本为不受约束的识别结果
+    /// When the request sets a pattern, whether the text is a pattern match; without a match
+    /// the text is the unconstrained recognition result
+    pub matches_pattern: Option<bool>,
+}
+
+/// 文本行中一段文本（字符或词）及其位置
+///
+/// A piece of text (character or word) within a text line and its location
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextSpan {
+    /// 文本内容
+    /// Text content
+    pub text: String,
+    /// 识别置信度，由各字符置信度按识别器的 [`ScoreAggregation`] 合并（0~1）
+    /// Recognition confidence, combined from the character confidences using the recognizer's
+    /// [`ScoreAggregation`] (0~1)
+    pub score: f32,
+    /// 是否含有置信度低于识别器阈值的字符
+    /// Whether any character scored below the recognizer's threshold
+    pub uncertain: bool,
+    /// 在原图中的四边形框
+    /// Quadrilateral in the original image
+    pub quad: TextQuad,
+}
+
+/// 单张文本行图像的识别结果
+///
+/// Recognition result of a single text line image
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LineRecognition {
+    /// 识别出的文本
+    /// Recognized text
+    pub text: String,
+    /// 识别置信度，由各字符置信度按识别器的 [`ScoreAggregation`] 合并（0~1）
+    /// Recognition confidence, combined from the character confidences using the recognizer's
+    /// [`ScoreAggregation`] (0~1)
+    pub score: f32,
+    /// 各字符及其在文本行图像中的范围
+    /// Every character with its extent in the text line image
+    pub spans: Vec<CharSpan>,
+    /// 设置了模式时，文本是否为满足置信度要求的模式匹配结果；未设置模式时为 `None`
+    /// When a pattern is set, whether the text is a pattern match meeting the confidence
+    /// requirement; `None` without a pattern
+    pub matches_pattern: Option<bool>,
+}
+
+/// 识别出的字符及其在文本行图像中的水平范围
+///
+/// A recognized character and its horizontal extent in the text line image
+#[derive(Debug, Clone, PartialEq)]
+pub struct CharSpan {
+    /// 识别出的字符，即一个字典条目，可能由多个码点组成
+    /// Recognized character, i.e. one dictionary entry, which may span several code points
+    pub text: String,
+    /// 识别置信度（0~1）
+    /// Recognition confidence (0~1)
+    pub score: f32,
+    /// 置信度是否低于识别器阈值
+    /// Whether the confidence is below the recognizer's threshold
+    pub uncertain: bool,
+    /// 起始位置，占文本行图像宽度的比例（0~1）
+    /// Start position as a fraction of the line image width (0~1)
+    pub start: f32,
+    /// 结束位置，占文本行图像宽度的比例（0~1）
+    /// End position as a fraction of the line image width (0~1)
+    pub end: f32,
+}
+
+/// 识别出的单个字符及其候选字符
+///
+/// A single recognized character together with its alternatives
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecognizedChar {
+    /// 识别出的字符，即一个字典条目，可能由多个码点组成
+    /// Recognized character, i.e. one dictionary entry, which may span several code points
+    pub text: String,
+    /// 识别置信度（0~1）
+    /// Recognition confidence (0~1)
+    pub score: f32,
+    /// 置信度是否低于识别器阈值
+    /// Whether the confidence is below the recognizer's threshold
+    pub uncertain: bool,
+    /// 字符所在的时间步
+    /// Time step of the character
+    pub timestep: usize,
+    /// 同一时间步上除该字符外概率最高的候选字符及其概率，按概率从高到低排列
+    /// The most probable other characters at the same time step with their probabilities,
+    /// from most to least probable
+    pub alternatives: Vec<(String, f32)>,
+}
+
+/// 整页图像的OCR结果
+///
+/// OCR result of a whole page
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageResult {
+    /// 检测到的页面顺时针旋转角度（0/90/180/270），识别前图像已被转回正向，
+    /// 文本框坐标对应转正后的图像
+    /// Detected clockwise rotation of the page (0/90/180/270). The image is turned upright
+    /// before recognition, and box coordinates refer to the upright image
+    pub angle: u32,
+    /// 按检测顺序排列的文本行
+    /// Text lines in detection order
+    pub lines: Vec<TextLine>,
+}
+
+/// 是否为不以空白分词的中日文字符（汉字、假名及全角标点）
+fn is_cjk(ch: char) -> bool {
+    matches!(ch,
+        '\u{3000}'..='\u{30FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FF00}'..='\u{FFEF}'
+        | '\u{20000}'..='\u{2FA1F}')
+}
+
+/// 将文本行内的字符范围映射到原图中的四边形，并按空白和中日文字符分组为词
+///
+/// Map character extents within a text line onto quads in the original image, and group them
+/// into words at whitespace and CJK characters
+pub(crate) fn line_spans(
+    spans: &[CharSpan],
+    quad: &TextQuad,
+    aggregation: ScoreAggregation,
+) -> (Vec<TextSpan>, Vec<TextSpan>) {
+    let chars = spans
+        .iter()
+        .map(|span| TextSpan {
+            text: span.text.clone(),
+            score: span.score,
+            uncertain: span.uncertain,
+            quad: quad.span(span.start, span.end),
+        })
+        .collect();
+
+    let mut groups: Vec<&[CharSpan]> = Vec::new();
+    let mut start = None;
+    for (i, span) in spans.iter().enumerate() {
+        let whitespace = span.text.chars().all(char::is_whitespace);
+        let cjk = span.text.chars().next().is_some_and(is_cjk);
+        if whitespace || cjk {
+            if let Some(s) = start.take() {
+                groups.push(&spans[s..i]);
+            }
+            if cjk {
+                groups.push(&spans[i..=i]);
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        groups.push(&spans[s..]);
+    }
+
+    let words = groups
+        .into_iter()
+        .map(|group| TextSpan {
+            text: group.iter().map(|x| x.text.as_str()).collect(),
+            score: aggregation.aggregate(group.iter().map(|x| x.score)),
+            uncertain: group.iter().any(|x| x.uncertain),
+            quad: quad.span(group[0].start, group[group.len() - 1].end),
+        })
+        .collect();
+
+    (chars, words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imageproc::point::Point;
+
+    #[test]
+    fn test_line_spans() {
+        let span = |text: &str, start, end| CharSpan {
+            text: text.to_string(),
+            score: 1.0,
+            uncertain: false,
+            start,
+            end,
+        };
+        let spans = [
+            span("A", 0.0, 0.1),
+            span("B", 0.1, 0.2),
+            span(" ", 0.2, 0.3),
+            span("中", 0.3, 0.5),
+            span("文", 0.5, 0.7),
+            span("C", 0.7, 1.0),
+        ];
+        let quad = TextQuad::from_rect(&Rect::at(0, 0).of_size(100, 10));
+        let (chars, words) = line_spans(&spans, &quad, ScoreAggregation::Mean);
+
+        assert_eq!(chars.len(), 6);
+        let texts: Vec<&str> = words.iter().map(|x| x.text.as_str()).collect();
+        assert_eq!(texts, ["AB", "中", "文", "C"]);
+        assert_eq!(words[0].quad.points[1], Point::new(20.0, 0.0));
+        assert_eq!(words[3].quad.points[0], Point::new(70.0, 0.0));
+    }
+
+    #[test]
+    fn test_score_aggregation() {
+        let scores = [0.9, 0.4, 0.9];
+        assert!((ScoreAggregation::Mean.aggregate(scores) - 0.7333).abs() < 1e-3);
+        assert_eq!(ScoreAggregation::Min.aggregate(scores), 0.4);
+        assert!((ScoreAggregation::GeometricMean.aggregate(scores) - 0.6868).abs() < 1e-3);
+        assert_eq!(ScoreAggregation::Min.aggregate([]), 0.0);
+        assert_eq!(
+            "geometric-mean".parse::<ScoreAggregation>().unwrap(),
+            ScoreAggregation::GeometricMean
+        );
+    }
+}
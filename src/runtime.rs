@@ -0,0 +1,206 @@
+use std::str::FromStr;
+
+use mnn::{BackendConfig, ScheduleConfig};
+
+use crate::error::OcrError;
+
+pub use mnn::ForwardType;
+
+/// 推理精度
+///
+/// Inference precision
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Precision {
+    /// 后端默认精度
+    /// The backend's default precision
+    Normal = 0,
+    /// 高精度，不使用 fp16
+    /// High precision, without fp16
+    High = 1,
+    /// 低精度，允许使用 fp16 以提升速度
+    /// Low precision, allowing fp16 for speed
+    #[default]
+    Low = 2,
+}
+
+/// 功耗模式
+///
+/// Power mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PowerMode {
+    /// 均衡
+    /// Balanced
+    Normal = 0,
+    /// 高性能
+    /// High performance
+    #[default]
+    High = 1,
+    /// 低功耗
+    /// Low power
+    Low = 2,
+}
+
+/// 内存使用模式
+///
+/// Memory usage mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MemoryMode {
+    /// 均衡
+    /// Balanced
+    #[default]
+    Normal = 0,
+    /// 以内存换速度
+    /// Trade memory for speed
+    High = 1,
+    /// 尽量节省内存
+    /// Save as much memory as possible
+    Low = 2,
+}
+
+macro_rules! impl_mode_from_str {
+    ($ty:ident, $name:literal) => {
+        impl FromStr for $ty {
+            type Err = OcrError;
+
+            /// 解析 normal、high 或 low
+            ///
+            /// Parse normal, high or low
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s.to_ascii_lowercase().as_str() {
+                    "normal" => Ok(Self::Normal),
+                    "high" => Ok(Self::High),
+                    "low" => Ok(Self::Low),
+                    _ => Err(OcrError::InputError(format!(
+                        concat!("Unknown ", $name, ": {}"),
+                        s
+                    ))),
+                }
+            }
+        }
+    };
+}
+
+impl_mode_from_str!(Precision, "precision");
+impl_mode_from_str!(PowerMode, "power mode");
+impl_mode_from_str!(MemoryMode, "memory mode");
+
+/// MNN 推理运行时设置，在创建会话时生效
+///
+/// MNN inference runtime settings, applied when a session is created
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RuntimeOptions {
+    /// 推理线程数，为 `None` 时使用 MNN 的默认值
+    /// Number of inference threads; `None` uses the MNN default
+    pub threads: Option<u32>,
+    /// 推理精度
+    /// Inference precision
+    pub precision: Precision,
+    /// 功耗模式
+    /// Power mode
+    pub power_mode: PowerMode,
+    /// 推理后端
+    /// Inference backend
+    pub forward_type: ForwardType,
+    /// 内存使用模式
+    /// Memory usage mode
+    pub memory_mode: MemoryMode,
+}
+
+impl Default for RuntimeOptions {
+    fn default() -> Self {
+        Self {
+            threads: None,
+            precision: Precision::default(),
+            power_mode: PowerMode::default(),
+            forward_type: ForwardType::Auto,
+            memory_mode: MemoryMode::default(),
+        }
+    }
+}
+
+impl RuntimeOptions {
+    /// 设置推理线程数
+    ///
+    /// Set the number of inference threads
+    pub fn with_threads(mut self, threads: u32) -> Self {
+        self.threads = Some(threads.max(1));
+        self
+    }
+
+    /// 设置推理精度
+    ///
+    /// Set the inference precision
+    pub fn with_precision(mut self, precision: Precision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// 设置功耗模式
+    ///
+    /// Set the power mode
+    pub fn with_power_mode(mut self, power_mode: PowerMode) -> Self {
+        self.power_mode = power_mode;
+        self
+    }
+
+    /// 设置推理后端
+    ///
+    /// Set the inference backend
+    pub fn with_forward_type(mut self, forward_type: ForwardType) -> Self {
+        self.forward_type = forward_type;
+        self
+    }
+
+    /// 设置内存使用模式
+    ///
+    /// Set the memory usage mode
+    pub fn with_memory_mode(mut self, memory_mode: MemoryMode) -> Self {
+        self.memory_mode = memory_mode;
+        self
+    }
+
+    /// 生成创建会话使用的调度配置
+    pub(crate) fn schedule_config(&self) -> ScheduleConfig {
+        let mut config = ScheduleConfig::new();
+        config.set_type(self.forward_type);
+        if let Some(threads) = self.threads {
+            config.set_num_threads(threads as i32);
+        }
+
+        let mut backend_config = BackendConfig::new();
+        backend_config.set_precision_mode(match self.precision {
+            Precision::Normal => mnn::PrecisionMode::Normal,
+            Precision::High => mnn::PrecisionMode::High,
+            Precision::Low => mnn::PrecisionMode::Low,
+        });
+        backend_config.set_power_mode(match self.power_mode {
+            PowerMode::Normal => mnn::PowerMode::Normal,
+            PowerMode::High => mnn::PowerMode::High,
+            PowerMode::Low => mnn::PowerMode::Low,
+        });
+        backend_config.set_memory_mode(match self.memory_mode {
+            MemoryMode::Normal => mnn::MemoryMode::Normal,
+            MemoryMode::High => mnn::MemoryMode::High,
+            MemoryMode::Low => mnn::MemoryMode::Low,
+        });
+
+        config.set_backend_config(backend_config);
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runtime_options() {
+        let runtime = RuntimeOptions::default()
+            .with_threads(0)
+            .with_precision("normal".parse().unwrap());
+        assert_eq!(runtime.threads, Some(1));
+        assert_eq!(runtime.precision, Precision::Normal);
+        assert_eq!(runtime.power_mode, PowerMode::High);
+        assert!("fp16".parse::<Precision>().is_err());
+    }
+}